@@ -0,0 +1,260 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+// how a date falling on a weekend or holiday moves to a business day
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BusinessDayConvention {
+    #[default]
+    Unadjusted,
+    Following,         // the next business day
+    ModifiedFollowing, // the next business day, unless it is in the next month
+    Preceding,         // the previous business day
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HolidayCalendar {
+    #[default]
+    None, // weekends only
+    UsFederal,
+    Nyse,
+    Custom(Vec<NaiveDate>),
+}
+
+impl HolidayCalendar {
+    // load holidays from a file with one date (YYYY-MM-DD) per line. Blank lines and lines
+    // starting with # are ignored.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut holidays = Vec::new();
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match NaiveDate::parse_from_str(line, "%Y-%m-%d") {
+                Ok(date) => holidays.push(date),
+                Err(err) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid holiday {}: {}", line, err),
+                    ))
+                }
+            }
+        }
+        holidays.sort();
+        holidays.dedup();
+        Ok(HolidayCalendar::Custom(holidays))
+    }
+
+    pub fn is_holiday(&self, &date: &NaiveDate) -> bool {
+        match self {
+            HolidayCalendar::None => false,
+            HolidayCalendar::UsFederal => get_us_federal_holidays(date.year()).contains(&date),
+            HolidayCalendar::Nyse => get_nyse_holidays(date.year()).contains(&date),
+            HolidayCalendar::Custom(holidays) => holidays.contains(&date),
+        }
+    }
+
+    pub fn is_business_day(&self, &date: &NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(&date)
+    }
+}
+
+// move date to a business day under convention
+pub fn adjust_date(
+    &date: &NaiveDate,
+    &convention: &BusinessDayConvention,
+    calendar: &HolidayCalendar,
+) -> NaiveDate {
+    let step = |date: NaiveDate, forward: bool| {
+        let mut date = date;
+        while !calendar.is_business_day(&date) {
+            date = if forward {
+                date.succ_opt().unwrap()
+            } else {
+                date.pred_opt().unwrap()
+            };
+        }
+        date
+    };
+
+    match convention {
+        BusinessDayConvention::Unadjusted => date,
+        BusinessDayConvention::Following => step(date, true),
+        BusinessDayConvention::Preceding => step(date, false),
+        BusinessDayConvention::ModifiedFollowing => {
+            let following = step(date, true);
+            if following.month() == date.month() {
+                following
+            } else {
+                step(date, false)
+            }
+        }
+    }
+}
+
+// the nth weekday of a month, counting from 1. n of 5 returns the last such weekday.
+fn get_nth_weekday(yr: i32, mon: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(yr, mon, weekday, n)
+        .or_else(|| NaiveDate::from_weekday_of_month_opt(yr, mon, weekday, n - 1))
+        .unwrap()
+}
+
+// a fixed-date holiday on a weekend is observed on the Friday before or the Monday after
+fn get_observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date.pred_opt().unwrap(),
+        Weekday::Sun => date.succ_opt().unwrap(),
+        _ => date,
+    }
+}
+
+// Easter Sunday in the Gregorian calendar (anonymous Gregorian algorithm)
+fn get_easter(yr: i32) -> NaiveDate {
+    let a = yr % 19;
+    let b = yr / 100;
+    let c = yr % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let mon = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(yr, mon as u32, day as u32).unwrap()
+}
+
+// holidays observed by the federal government in yr, including New Year's Day of the following
+// year when it is observed on December 31
+fn get_us_federal_holidays(yr: i32) -> Vec<NaiveDate> {
+    let date = |mon, day| NaiveDate::from_ymd_opt(yr, mon, day).unwrap();
+    let mut holidays = vec![
+        get_observed(date(1, 1)),
+        get_nth_weekday(yr, 1, Weekday::Mon, 3), // Martin Luther King Jr. Day
+        get_nth_weekday(yr, 2, Weekday::Mon, 3), // Washington's Birthday
+        get_nth_weekday(yr, 5, Weekday::Mon, 5), // Memorial Day
+        get_observed(date(7, 4)),
+        get_nth_weekday(yr, 9, Weekday::Mon, 1),  // Labor Day
+        get_nth_weekday(yr, 10, Weekday::Mon, 2), // Columbus Day
+        get_observed(date(11, 11)),
+        get_nth_weekday(yr, 11, Weekday::Thu, 4), // Thanksgiving Day
+        get_observed(date(12, 25)),
+        get_observed(NaiveDate::from_ymd_opt(yr + 1, 1, 1).unwrap()),
+    ];
+    if yr >= 2021 {
+        holidays.push(get_observed(date(6, 19)));
+    }
+    holidays
+}
+
+// days the New York Stock Exchange is closed in yr. The exchange does not close for a New
+// Year's Day falling on a Saturday, nor for Columbus Day or Veterans Day, but closes on Good
+// Friday.
+fn get_nyse_holidays(yr: i32) -> Vec<NaiveDate> {
+    let date = |mon, day| NaiveDate::from_ymd_opt(yr, mon, day).unwrap();
+    let mut holidays = vec![
+        get_nth_weekday(yr, 1, Weekday::Mon, 3),
+        get_nth_weekday(yr, 2, Weekday::Mon, 3),
+        get_easter(yr) - chrono::Days::new(2), // Good Friday
+        get_nth_weekday(yr, 5, Weekday::Mon, 5),
+        get_observed(date(7, 4)),
+        get_nth_weekday(yr, 9, Weekday::Mon, 1),
+        get_nth_weekday(yr, 11, Weekday::Thu, 4),
+        get_observed(date(12, 25)),
+    ];
+    if date(1, 1).weekday() != Weekday::Sat {
+        holidays.push(get_observed(date(1, 1)));
+    }
+    if yr >= 2022 {
+        holidays.push(get_observed(date(6, 19)));
+    }
+    holidays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adjust_date, get_easter, BusinessDayConvention, HolidayCalendar};
+    use chrono::NaiveDate;
+    use std::{env, fs};
+    use test_log::test;
+
+    fn date(yr: i32, mon: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yr, mon, day).unwrap()
+    }
+
+    #[test]
+    fn test_holidays() {
+        let federal = HolidayCalendar::UsFederal;
+        assert!(federal.is_holiday(&date(2024, 1, 15)));
+        assert!(federal.is_holiday(&date(2024, 5, 27)));
+        assert!(federal.is_holiday(&date(2024, 11, 28)));
+        assert!(federal.is_holiday(&date(2021, 12, 31))); // New Year's Day 2022 is a Saturday
+        assert!(federal.is_holiday(&date(2023, 1, 2)));
+        assert!(federal.is_holiday(&date(2024, 10, 14)));
+        assert!(!federal.is_holiday(&date(2024, 3, 29)));
+
+        let nyse = HolidayCalendar::Nyse;
+        assert_eq!(get_easter(2024), date(2024, 3, 31));
+        assert!(nyse.is_holiday(&date(2024, 3, 29)));
+        assert!(!nyse.is_holiday(&date(2024, 10, 14)));
+        assert!(!nyse.is_holiday(&date(2021, 12, 31)));
+        assert!(!nyse.is_business_day(&date(2024, 3, 30)));
+    }
+
+    #[test]
+    fn test_adjust_date() {
+        let calendar = HolidayCalendar::UsFederal;
+        let adjust = |date, convention| adjust_date(&date, &convention, &calendar);
+
+        // Saturday, August 31, 2024 and Labor Day, September 2
+        let saturday = date(2024, 8, 31);
+        assert_eq!(
+            adjust(saturday, BusinessDayConvention::Unadjusted),
+            saturday
+        );
+        assert_eq!(
+            adjust(saturday, BusinessDayConvention::Following),
+            date(2024, 9, 3)
+        );
+        assert_eq!(
+            adjust(saturday, BusinessDayConvention::ModifiedFollowing),
+            date(2024, 8, 30)
+        );
+        assert_eq!(
+            adjust(saturday, BusinessDayConvention::Preceding),
+            date(2024, 8, 30)
+        );
+        assert_eq!(
+            adjust(date(2024, 6, 15), BusinessDayConvention::ModifiedFollowing),
+            date(2024, 6, 17)
+        );
+    }
+
+    #[test]
+    fn test_calendar_from_file() {
+        let path = env::temp_dir().join("finance_test_holidays.txt");
+        fs::write(&path, "# company holidays\n2024-12-24\n\n2024-07-05\n").unwrap();
+        let calendar = HolidayCalendar::from_file(&path).unwrap();
+        assert_eq!(
+            calendar,
+            HolidayCalendar::Custom(vec![date(2024, 7, 5), date(2024, 12, 24)])
+        );
+        assert!(calendar.is_holiday(&date(2024, 12, 24)));
+
+        // a calendar built directly need not be in date order
+        let unsorted = HolidayCalendar::Custom(vec![date(2024, 12, 24), date(2024, 7, 5)]);
+        assert!(unsorted.is_holiday(&date(2024, 7, 5)));
+        assert!(!unsorted.is_business_day(&date(2024, 12, 24)));
+
+        fs::write(&path, "2024-13-01\n").unwrap();
+        assert!(HolidayCalendar::from_file(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::NaiveDate;
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// a dated cash flow. Money received is positive, money paid out is negative.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CashFlow {
+    pub cf_date: NaiveDate,
+    pub cf_amount: f64,
+}
+
+impl CashFlow {
+    pub fn new(cf_date: NaiveDate, cf_amount: f64) -> Self {
+        Self { cf_date, cf_amount }
+    }
+}
+
+impl fmt::Display for CashFlow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "date {}, amount ${:.4}", self.cf_date, self.cf_amount)
+    }
+}
+
+// years from the first cash flow, on an actual/365 basis
+fn get_years(flows: &[CashFlow]) -> Vec<f64> {
+    let first_date = flows.iter().map(|cf| cf.cf_date).min().unwrap_or_default();
+    flows
+        .iter()
+        .map(|cf| cf.cf_date.signed_duration_since(first_date).num_days() as f64 / 365.)
+        .collect()
+}
+
+// net present value of dated cash flows as of the first cash flow date. annual_rate is an
+// effective annual rate (i.e., 7.0).
+pub fn xnpv(&annual_rate: &f64, flows: &[CashFlow]) -> f64 {
+    let rate = annual_rate / 100.;
+    flows
+        .iter()
+        .zip(get_years(flows))
+        .map(|(cf, years)| cf.cf_amount / (1. + rate).powf(years))
+        .sum()
+}
+
+// internal rate of return of dated cash flows, as an effective annual rate (i.e., 7.0). Returns
+// None if the flows do not include both payments and receipts or no rate can be found.
+pub fn xirr(flows: &[CashFlow]) -> Option<f64> {
+    if !flows.iter().any(|cf| cf.cf_amount > 0.) || !flows.iter().any(|cf| cf.cf_amount < 0.) {
+        return None;
+    }
+
+    let years = get_years(flows);
+    let npv = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .zip(&years)
+            .map(|(cf, years)| cf.cf_amount / (1. + rate).powf(*years))
+            .sum()
+    };
+    let npv_slope = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .zip(&years)
+            .map(|(cf, years)| -years * cf.cf_amount / (1. + rate).powf(years + 1.))
+            .sum()
+    };
+
+    // Newton's method converges quickly from a reasonable guess
+    let mut rate = 0.1;
+    for i in 0..50 {
+        let slope = npv_slope(rate);
+        if slope == 0. || !slope.is_finite() {
+            break;
+        }
+        let next_rate = rate - npv(rate) / slope;
+        if !next_rate.is_finite() || next_rate <= -1. {
+            break;
+        }
+        if (next_rate - rate).abs() < 1e-12 {
+            trace!("xirr converged in {} iterations", i);
+            return Some(next_rate * 100.);
+        }
+        rate = next_rate;
+    }
+
+    // otherwise bisect between a near total loss and a very large return
+    let mut low = -0.9999;
+    let mut high = 10.;
+    if npv(low).signum() == npv(high).signum() {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.;
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2. * 100.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{xirr, xnpv, CashFlow};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_xirr() {
+        let flows = vec![
+            CashFlow::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), -10000.),
+            CashFlow::new(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), 2750.),
+            CashFlow::new(NaiveDate::from_ymd_opt(2024, 10, 30).unwrap(), 4250.),
+            CashFlow::new(NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(), 3250.),
+            CashFlow::new(NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(), 2750.),
+        ];
+        let rate = xirr(&flows).unwrap();
+        assert!((rate - 37.3363).abs() < 0.0001);
+        assert!(xnpv(&rate, &flows).abs() < 1e-6);
+
+        // one year at 10% exactly
+        let flows = vec![
+            CashFlow::new(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), -1000.),
+            CashFlow::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 1100.),
+        ];
+        assert!((xirr(&flows).unwrap() - 10.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_xirr_no_solution() {
+        let flows = vec![
+            CashFlow::new(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), 1000.),
+            CashFlow::new(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 1100.),
+        ];
+        assert_eq!(xirr(&flows), None);
+        assert_eq!(xirr(&[]), None);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::loan::{get_period_interest_rate, round, Compounding, Loan, PmtSchedule};
+use chrono::{Months, NaiveDate};
+use log::{trace, warn};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstructionDraw {
+    pub draw_date: NaiveDate,
+    pub amount: f64,
+}
+
+// interest for a month of construction, due on pmt_date
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstructionInterest {
+    pub pmt_number: i32,
+    pub pmt_date: NaiveDate,
+    pub draws: f64,
+    pub interest: f64,
+    pub reserve_paid: f64,  // interest paid from the interest reserve
+    pub borrower_paid: f64, // interest paid by the borrower
+    pub reserve_balance: f64,
+    pub end_balance: f64, // disbursed balance, including interest paid from the reserve
+}
+
+impl fmt::Display for ConstructionInterest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pmt number {}, date {}, draws ${:.2}, interest ${:.2}, reserve paid ${:.2}, borrower paid ${:.2}, reserve balance ${:.2}, ending balance ${:.2}",
+            self.pmt_number,
+            self.pmt_date,
+            self.draws,
+            self.interest,
+            self.reserve_paid,
+            self.borrower_paid,
+            self.reserve_balance,
+            self.end_balance
+        )
+    }
+}
+
+// a construction-to-permanent loan. Funds are disbursed in draws up to the commitment and are
+// interest-only until the conversion date, when the disbursed balance converts to a permanent
+// loan amortizing over term. An interest reserve set aside from the commitment pays the monthly
+// construction interest until it runs out; reserve payments are disbursed like any other draw.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstructionLoan {
+    pub commitment: f64,
+    pub annual_rate: f64, // construction period rate
+    pub loan_date: NaiveDate,
+    pub conversion_date: NaiveDate,
+    pub interest_reserve: f64,
+    pub term: f64,      // years the permanent loan amortizes over
+    pub perm_rate: f64, // permanent loan rate
+    pub pmt_schedule: PmtSchedule,
+    pub compound_type: Compounding,
+    pub dec_places: f64,
+    draws: Vec<ConstructionDraw>, // in date order
+}
+
+impl ConstructionLoan {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        commitment: f64,
+        annual_rate: f64,
+        loan_date: NaiveDate,
+        conversion_date: NaiveDate,
+        term: f64,
+        perm_rate: f64,
+        pmt_schedule: PmtSchedule,
+        compound_type: Compounding,
+        dec_places: f64,
+    ) -> Self {
+        Self {
+            commitment,
+            annual_rate,
+            loan_date,
+            conversion_date,
+            term,
+            perm_rate,
+            pmt_schedule,
+            compound_type,
+            dec_places,
+            ..Default::default()
+        }
+    }
+
+    // set aside part of the commitment to pay construction interest
+    pub fn set_interest_reserve(&mut self, interest_reserve: f64) -> Option<f64> {
+        let drawn = self.get_total_draws();
+        if interest_reserve < 0. || drawn + interest_reserve > self.commitment {
+            warn!(
+                "interest reserve of {} is not valid with {} drawn of {}",
+                interest_reserve, drawn, self.commitment
+            );
+            return None;
+        }
+        self.interest_reserve = interest_reserve;
+        Some(interest_reserve)
+    }
+
+    // schedule a draw. Returns None if it falls outside the construction period or the
+    // commitment, less the interest reserve, would be exceeded.
+    pub fn add_draw(&mut self, draw_date: NaiveDate, amount: f64) -> Option<ConstructionDraw> {
+        let available = self.commitment - self.interest_reserve - self.get_total_draws();
+        if amount <= 0.
+            || draw_date < self.loan_date
+            || draw_date >= self.conversion_date
+            || amount > available
+        {
+            warn!(
+                "draw of {} on {} is not valid; {} is available",
+                amount, draw_date, available
+            );
+            return None;
+        }
+        let draw = ConstructionDraw {
+            draw_date,
+            amount: round(amount, self.dec_places),
+        };
+        self.draws.push(draw);
+        self.draws.sort_by_key(|draw| draw.draw_date);
+        Some(draw)
+    }
+
+    pub fn get_draws(&self) -> &[ConstructionDraw] {
+        &self.draws
+    }
+
+    pub fn get_total_draws(&self) -> f64 {
+        round(
+            self.draws.iter().map(|draw| draw.amount).sum(),
+            self.dec_places,
+        )
+    }
+
+    // monthly interest-only payments on the disbursed balance from the loan date to the
+    // conversion date, which ends a short last period. Interest accrues by the day.
+    pub fn get_interest_projection(&self) -> Vec<ConstructionInterest> {
+        let accrue = |balance: f64, begin_date: &NaiveDate, end_date: &NaiveDate| -> f64 {
+            balance
+                * get_period_interest_rate(
+                    &self.annual_rate,
+                    &PmtSchedule::Monthly,
+                    &Compounding::Daily,
+                    begin_date,
+                    end_date,
+                )
+        };
+
+        let mut projection = Vec::new();
+        let mut balance = 0.;
+        let mut reserve_balance = round(self.interest_reserve, self.dec_places);
+        let mut begin_date = self.loan_date;
+        let mut pmt_number = 0;
+        while begin_date < self.conversion_date {
+            pmt_number += 1;
+            let pmt_date = self
+                .loan_date
+                .checked_add_months(Months::new(pmt_number as u32))
+                .unwrap()
+                .min(self.conversion_date);
+
+            let mut interest = 0.;
+            let mut draws = 0.;
+            let mut accrued_from = begin_date;
+            for draw in self
+                .draws
+                .iter()
+                .filter(|draw| draw.draw_date >= begin_date && draw.draw_date < pmt_date)
+            {
+                interest += accrue(balance, &accrued_from, &draw.draw_date);
+                balance += draw.amount;
+                draws += draw.amount;
+                accrued_from = draw.draw_date;
+            }
+            interest = round(
+                interest + accrue(balance, &accrued_from, &pmt_date),
+                self.dec_places,
+            );
+
+            let reserve_paid = interest.min(reserve_balance);
+            reserve_balance = round(reserve_balance - reserve_paid, self.dec_places);
+            balance = round(balance + reserve_paid, self.dec_places);
+            let row = ConstructionInterest {
+                pmt_number,
+                pmt_date,
+                draws: round(draws, self.dec_places),
+                interest,
+                reserve_paid,
+                borrower_paid: round(interest - reserve_paid, self.dec_places),
+                reserve_balance,
+                end_balance: balance,
+            };
+            trace!("construction interest {}", row);
+            projection.push(row);
+            begin_date = pmt_date;
+        }
+        projection
+    }
+
+    pub fn get_construction_interest(&self) -> f64 {
+        round(
+            self.get_interest_projection()
+                .iter()
+                .map(|row| row.interest)
+                .sum(),
+            self.dec_places,
+        )
+    }
+
+    // the disbursed balance that converts to the permanent loan
+    pub fn get_conversion_balance(&self) -> f64 {
+        self.get_interest_projection()
+            .last()
+            .map_or(0., |row| row.end_balance)
+    }
+
+    // the permanent loan dated on the conversion date, amortizing the conversion balance
+    pub fn get_permanent_loan(&self, first_pmt_date: NaiveDate) -> Loan {
+        Loan::new(
+            self.get_conversion_balance(),
+            self.term,
+            self.perm_rate,
+            self.pmt_schedule,
+            self.compound_type,
+            self.conversion_date,
+            first_pmt_date,
+            self.dec_places,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstructionLoan;
+    use crate::loan::{Compounding, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn date(yr: i32, mon: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yr, mon, day).unwrap()
+    }
+
+    #[test]
+    fn test_construction_interest() {
+        let mut loan = ConstructionLoan::new(
+            400000.,
+            8.,
+            date(2024, 1, 1),
+            date(2024, 10, 15),
+            30.,
+            6.5,
+            PmtSchedule::Monthly,
+            Compounding::Monthly,
+            2.,
+        );
+        loan.add_draw(date(2024, 1, 1), 100000.).unwrap();
+        loan.add_draw(date(2024, 3, 16), 120000.).unwrap();
+        loan.add_draw(date(2024, 6, 1), 100000.).unwrap();
+        assert_eq!(loan.add_draw(date(2024, 10, 15), 1000.), None);
+        assert_eq!(loan.add_draw(date(2024, 7, 1), 90000.), None);
+
+        let projection = loan.get_interest_projection();
+        assert_eq!(projection.len(), 10);
+        assert_eq!(projection[9].pmt_date, date(2024, 10, 15));
+        assert_eq!(projection[0].draws, 100000.);
+        assert_eq!(projection[0].borrower_paid, projection[0].interest);
+        // interest on the second draw starts mid-month
+        assert!(projection[2].interest > projection[1].interest);
+        assert!(projection[3].interest > projection[2].interest);
+        assert_eq!(projection[9].end_balance, 320000.);
+        assert_eq!(loan.get_conversion_balance(), loan.get_total_draws());
+
+        // the short last period accrues half a month of interest
+        assert!(projection[9].interest < projection[8].interest * 0.55);
+
+        let perm = loan.get_permanent_loan(date(2024, 12, 1));
+        assert_eq!(perm.get_pmt_count(), 360);
+        assert_eq!(perm.get_scheduled_pmts()[0].pmt_date, date(2024, 12, 1));
+        assert!(*perm.get_pmt_amount() > 2000. && *perm.get_pmt_amount() < 2030.);
+
+        assert_eq!(loan.set_interest_reserve(90000.), None);
+
+        let total_interest = loan.get_construction_interest();
+        loan.set_interest_reserve(10000.).unwrap();
+
+        // the reserve pays interest until it runs out, then the borrower pays
+        let projection = loan.get_interest_projection();
+        assert_eq!(projection[0].reserve_paid, projection[0].interest);
+        assert_eq!(projection[0].borrower_paid, 0.);
+        assert_eq!(projection[9].reserve_balance, 0.);
+        assert!(projection[9].borrower_paid > 0.);
+
+        // interest paid from the reserve is disbursed and accrues interest itself
+        assert_eq!(loan.get_conversion_balance(), 330000.);
+        assert!(loan.get_construction_interest() > total_interest);
+        assert_eq!(loan.add_draw(date(2024, 7, 1), 75000.), None);
+    }
+}
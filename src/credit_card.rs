@@ -0,0 +1,320 @@
+use crate::loan::{get_period_interest_rate, round, Compounding, LoanPayment, PmtSchedule};
+use chrono::{Months, NaiveDate};
+use log::{trace, warn};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const MAX_PMTS: i32 = 1200; // give up on a payoff that takes longer than 100 years
+
+// the minimum payment is the greater of floor or pct_of_balance (i.e., 1.0) of the statement
+// balance, plus the statement's interest when include_interest is set. It is never more than
+// the balance.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MinPmtFormula {
+    pub floor: f64,
+    pub pct_of_balance: f64,
+    pub include_interest: bool,
+}
+
+impl Default for MinPmtFormula {
+    fn default() -> Self {
+        Self {
+            floor: 25.,
+            pct_of_balance: 1.,
+            include_interest: true,
+        }
+    }
+}
+
+impl MinPmtFormula {
+    pub fn get_min_pmt(&self, &balance: &f64, &interest: &f64) -> f64 {
+        let pct_pmt = if self.include_interest {
+            balance * self.pct_of_balance / 100. + interest
+        } else {
+            balance * self.pct_of_balance / 100.
+        };
+        pct_pmt.max(self.floor).min(balance)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PayoffStrategy {
+    #[default]
+    MinimumOnly, // pay the minimum each month as the balance falls
+    FixedAmount(f64), // pay the same amount each month, or the minimum if it is higher
+    TargetDate(NaiveDate), // pay the level amount that clears the balance by this date
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CardPayoff {
+    pub strategy: PayoffStrategy,
+    pub pmt_count: i32,
+    pub payoff_date: NaiveDate,
+    pub first_pmt: f64,
+    pub total_pmts: f64,
+    pub total_interest: f64,
+    pub total_purchases: f64,
+}
+
+impl fmt::Display for CardPayoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}: {} payments starting at ${:.2}, paid off {}, total paid ${:.2}, interest ${:.2}, purchases ${:.2}",
+            self.strategy,
+            self.pmt_count,
+            self.first_pmt,
+            self.payoff_date,
+            self.total_pmts,
+            self.total_interest,
+            self.total_purchases
+        )
+    }
+}
+
+// a credit card balance paid down monthly. Interest compounds daily at the APR between
+// statements, and monthly_purchases are charged to the card each month before the statement.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreditCard {
+    pub balance: f64,
+    pub apr: f64,
+    pub statement_date: NaiveDate, // date of the statement showing balance
+    pub min_pmt_formula: MinPmtFormula,
+    pub monthly_purchases: f64,
+    pub dec_places: f64,
+}
+
+impl CreditCard {
+    pub fn new(balance: f64, apr: f64, statement_date: NaiveDate, dec_places: f64) -> Self {
+        Self {
+            balance,
+            apr,
+            statement_date,
+            dec_places,
+            ..Default::default()
+        }
+    }
+
+    fn get_statement_date(&self, pmt_number: i32) -> NaiveDate {
+        self.statement_date
+            .checked_add_months(Months::new(pmt_number as u32))
+            .unwrap()
+    }
+
+    // monthly payments under strategy, one row per statement. pmt_end_balance includes the
+    // month's purchases, so pmt_principal_paid is the payment less interest. Returns None when the
+    // strategy never pays off the balance.
+    pub fn get_payoff_pmts(&self, &strategy: &PayoffStrategy) -> Option<Vec<LoanPayment>> {
+        let fixed_pmt = match strategy {
+            PayoffStrategy::MinimumOnly => 0.,
+            PayoffStrategy::FixedAmount(amount) => amount,
+            PayoffStrategy::TargetDate(target_date) => self.get_target_pmt(&target_date)?,
+        };
+
+        let mut pmts = Vec::new();
+        let mut balance = round(self.balance, self.dec_places);
+        let mut begin_date = self.statement_date;
+        let mut pmt_number = 0;
+        while balance > 0. {
+            if pmt_number >= MAX_PMTS {
+                warn!(
+                    "{:?} does not pay off the balance in {} payments",
+                    strategy, MAX_PMTS
+                );
+                return None;
+            }
+            pmt_number += 1;
+            let end_date = self.get_statement_date(pmt_number);
+            let interest = round(
+                balance
+                    * get_period_interest_rate(
+                        &self.apr,
+                        &PmtSchedule::Monthly,
+                        &Compounding::Daily,
+                        &begin_date,
+                        &end_date,
+                    ),
+                self.dec_places,
+            );
+            let due = balance + interest;
+            let min_pmt = self.min_pmt_formula.get_min_pmt(&due, &interest);
+            let pmt_amount = round(fixed_pmt.max(min_pmt).min(due), self.dec_places);
+            balance = round(due - pmt_amount, self.dec_places);
+            if balance > 0. {
+                balance = round(balance + self.monthly_purchases, self.dec_places);
+            }
+            trace!(
+                "pmt {} on {}: {}, interest {}, balance {}",
+                pmt_number,
+                end_date,
+                pmt_amount,
+                interest,
+                balance
+            );
+            pmts.push(LoanPayment::new(
+                pmt_number, end_date, pmt_amount, interest, balance,
+            ));
+            begin_date = end_date;
+        }
+        Some(pmts)
+    }
+
+    // the level monthly payment that pays off the balance with the last statement on or before
+    // target_date. The ending balance is linear in the payment, so two trial payments are enough
+    // to solve for it. Returns None when no statement falls on or before target_date.
+    pub fn get_target_pmt(&self, &target_date: &NaiveDate) -> Option<f64> {
+        let pmt_count = (1..=MAX_PMTS)
+            .take_while(|n| self.get_statement_date(*n) <= target_date)
+            .last();
+        let pmt_count = match pmt_count {
+            Some(pmt_count) => pmt_count,
+            None => {
+                warn!(
+                    "no statement falls on or before target date {}",
+                    target_date
+                );
+                return None;
+            }
+        };
+
+        let end_balance = |pmt_amount: f64| -> f64 {
+            let mut balance = self.balance;
+            let mut begin_date = self.statement_date;
+            for pmt_number in 1..=pmt_count {
+                let end_date = self.get_statement_date(pmt_number);
+                let rate = get_period_interest_rate(
+                    &self.apr,
+                    &PmtSchedule::Monthly,
+                    &Compounding::Daily,
+                    &begin_date,
+                    &end_date,
+                );
+                balance = balance * (1. + rate) - pmt_amount;
+                if pmt_number < pmt_count {
+                    balance += self.monthly_purchases;
+                }
+                begin_date = end_date;
+            }
+            balance
+        };
+
+        let unpaid = end_balance(0.);
+        let paid_per_dollar = unpaid - end_balance(1.);
+        // round up so the last payment is never more than the others
+        let factor = 10_f64.powf(self.dec_places);
+        Some((unpaid / paid_per_dollar * factor).ceil() / factor)
+    }
+
+    pub fn get_payoff(&self, &strategy: &PayoffStrategy) -> Option<CardPayoff> {
+        let pmts = self.get_payoff_pmts(&strategy)?;
+        let last_pmt = pmts.last()?;
+        Some(CardPayoff {
+            strategy,
+            pmt_count: last_pmt.pmt_number,
+            payoff_date: last_pmt.pmt_date,
+            first_pmt: pmts[0].pmt_amount,
+            total_pmts: round(pmts.iter().map(|pmt| pmt.pmt_amount).sum(), self.dec_places),
+            total_interest: round(
+                pmts.iter().map(|pmt| pmt.pmt_interest_paid).sum(),
+                self.dec_places,
+            ),
+            total_purchases: round(
+                self.monthly_purchases * (last_pmt.pmt_number - 1) as f64,
+                self.dec_places,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CreditCard, MinPmtFormula, PayoffStrategy};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn date(yr: i32, mon: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yr, mon, day).unwrap()
+    }
+
+    #[test]
+    fn test_min_pmt_formula() {
+        let formula = MinPmtFormula::default();
+        assert_eq!(formula.get_min_pmt(&5000., &80.), 130.);
+        assert_eq!(formula.get_min_pmt(&1000., &10.), 25.);
+        assert_eq!(formula.get_min_pmt(&20., &0.2), 20.);
+
+        let no_interest = MinPmtFormula {
+            pct_of_balance: 2.,
+            include_interest: false,
+            ..Default::default()
+        };
+        assert_eq!(no_interest.get_min_pmt(&5000., &80.), 100.);
+    }
+
+    #[test]
+    fn test_payoff_strategies() {
+        let card = CreditCard::new(5000., 22.99, date(2024, 1, 10), 2.);
+
+        let minimum = card.get_payoff(&PayoffStrategy::MinimumOnly).unwrap();
+        let fixed = card.get_payoff(&PayoffStrategy::FixedAmount(200.)).unwrap();
+        assert!(minimum.pmt_count > fixed.pmt_count);
+        assert!(minimum.total_interest > fixed.total_interest);
+        assert!(fixed.pmt_count > 30 && fixed.pmt_count < 36);
+        assert_eq!(
+            fixed.total_pmts,
+            ((5000. + fixed.total_interest) * 100.).round() / 100.
+        );
+
+        let pmts = card.get_payoff_pmts(&PayoffStrategy::MinimumOnly).unwrap();
+        assert_eq!(pmts[0].pmt_date, date(2024, 2, 10));
+        assert!(pmts[0].pmt_amount > 140. && pmts[0].pmt_amount < 150.);
+        assert_eq!(pmts.last().unwrap().pmt_end_balance, 0.);
+
+        // a payment below the interest never pays off the card without a minimum
+        let no_minimum = CreditCard {
+            min_pmt_formula: MinPmtFormula {
+                floor: 0.,
+                pct_of_balance: 0.,
+                include_interest: false,
+            },
+            ..card
+        };
+        assert_eq!(
+            no_minimum.get_payoff(&PayoffStrategy::FixedAmount(50.)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_target_date() {
+        let mut card = CreditCard::new(5000., 22.99, date(2024, 1, 10), 2.);
+        card.monthly_purchases = 100.;
+
+        let target = PayoffStrategy::TargetDate(date(2026, 1, 10));
+        let payoff = card.get_payoff(&target).unwrap();
+        assert_eq!(payoff.pmt_count, 24);
+        assert_eq!(payoff.payoff_date, date(2026, 1, 10));
+        assert_eq!(payoff.total_purchases, 2300.);
+
+        let pmts = card.get_payoff_pmts(&target).unwrap();
+        assert!(pmts[23].pmt_amount <= pmts[0].pmt_amount);
+        assert!(pmts[23].pmt_amount > pmts[0].pmt_amount - 1.);
+        assert_eq!(card.get_target_pmt(&date(2023, 12, 1)), None);
+
+        // a target between statements pays off with the statement before it
+        let between = PayoffStrategy::TargetDate(date(2026, 1, 25));
+        let payoff = card.get_payoff(&between).unwrap();
+        assert_eq!(payoff.pmt_count, 24);
+        assert_eq!(payoff.payoff_date, date(2026, 1, 10));
+        assert_eq!(
+            card.get_target_pmt(&date(2026, 1, 25)),
+            card.get_target_pmt(&date(2026, 1, 10))
+        );
+        assert_eq!(card.get_target_pmt(&date(2024, 2, 9)), None);
+    }
+}
@@ -0,0 +1,459 @@
+use crate::loan::{get_period_interest_rate, round, Compounding, Loan, PmtSchedule};
+use chrono::{Days, Months, NaiveDate};
+use log::{trace, warn};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const PMT_DUE_DAYS: u64 = 25; // the minimum payment is due 25 days after the statement closes
+
+// minimum payment during the draw period
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MinPmtRule {
+    #[default]
+    InterestOnly,
+    PctOfBalance(f64), // percent of the ending balance (i.e., 1.5), but no less than the interest
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TransactionType {
+    #[default]
+    Draw,
+    Payment,
+    Interest,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreditTransaction {
+    pub txn_date: NaiveDate,
+    pub txn_type: TransactionType,
+    pub amount: f64,
+    pub balance: f64, // balance after the transaction
+}
+
+impl fmt::Display for CreditTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "date {}, {:?} ${:.2}, balance ${:.2}",
+            self.txn_date, self.txn_type, self.amount, self.balance
+        )
+    }
+}
+
+// a monthly billing cycle ending on close_date
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreditStatement {
+    pub begin_date: NaiveDate,
+    pub close_date: NaiveDate,
+    pub begin_balance: f64,
+    pub draws: f64,
+    pub pmts: f64,
+    pub interest: f64,
+    pub end_balance: f64,
+    pub available_credit: f64,
+    pub min_pmt: f64,
+    pub due_date: NaiveDate,
+}
+
+impl fmt::Display for CreditStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} to {}: beginning balance ${:.2}, draws ${:.2}, payments ${:.2}, interest ${:.2}, ending balance ${:.2}, available ${:.2}, minimum payment ${:.2} due {}",
+            self.begin_date,
+            self.close_date,
+            self.begin_balance,
+            self.draws,
+            self.pmts,
+            self.interest,
+            self.end_balance,
+            self.available_credit,
+            self.min_pmt,
+            self.due_date
+        )
+    }
+}
+
+// a revolving line of credit, such as a HELOC. The borrower may draw up to the credit limit
+// during the draw period; the balance then amortizes monthly over the repayment period. Interest
+// accrues daily on the balance and is posted when each monthly statement closes.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreditLine {
+    pub credit_limit: f64,
+    pub annual_rate: f64,
+    pub open_date: NaiveDate, // statements close monthly on the day of the open date
+    pub draw_years: f64,
+    pub repay_years: f64,
+    pub min_pmt_rule: MinPmtRule,
+    pub dec_places: f64,
+    transactions: Vec<CreditTransaction>, // draws and payments in date order
+}
+
+impl CreditLine {
+    pub fn new(
+        credit_limit: f64,
+        annual_rate: f64,
+        open_date: NaiveDate,
+        draw_years: f64,
+        repay_years: f64,
+        min_pmt_rule: MinPmtRule,
+        dec_places: f64,
+    ) -> Self {
+        Self {
+            credit_limit,
+            annual_rate,
+            open_date,
+            draw_years,
+            repay_years,
+            min_pmt_rule,
+            dec_places,
+            transactions: Vec::new(),
+        }
+    }
+
+    fn add_months(&self, months: f64) -> NaiveDate {
+        self.open_date
+            .checked_add_months(Months::new(months.round() as u32))
+            .unwrap()
+    }
+
+    // last day draws are allowed
+    pub fn get_draw_end_date(&self) -> NaiveDate {
+        self.add_months(self.draw_years * 12.)
+    }
+
+    pub fn get_maturity_date(&self) -> NaiveDate {
+        self.add_months((self.draw_years + self.repay_years) * 12.)
+    }
+
+    // draw on the line. Returns None if the draw is outside the draw period or over the limit.
+    pub fn draw(&mut self, txn_date: NaiveDate, amount: f64) -> Option<CreditTransaction> {
+        let available = self.get_available_credit(&txn_date);
+        if amount <= 0.
+            || txn_date < self.open_date
+            || txn_date > self.get_draw_end_date()
+            || amount > available
+        {
+            warn!(
+                "draw of {} on {} is not valid; available credit is {}",
+                amount, txn_date, available
+            );
+            return None;
+        }
+        self.add_transaction(txn_date, TransactionType::Draw, amount)
+    }
+
+    // pay down the line. Returns None if the payment is more than the balance.
+    pub fn pay(&mut self, txn_date: NaiveDate, amount: f64) -> Option<CreditTransaction> {
+        let balance = self.get_balance(&txn_date);
+        if amount <= 0. || txn_date < self.open_date || amount > balance {
+            warn!(
+                "payment of {} on {} is not valid; balance is {}",
+                amount, txn_date, balance
+            );
+            return None;
+        }
+        self.add_transaction(txn_date, TransactionType::Payment, amount)
+    }
+
+    fn add_transaction(
+        &mut self,
+        txn_date: NaiveDate,
+        txn_type: TransactionType,
+        amount: f64,
+    ) -> Option<CreditTransaction> {
+        self.transactions.push(CreditTransaction {
+            txn_date,
+            txn_type,
+            amount: round(amount, self.dec_places),
+            balance: 0.,
+        });
+        self.transactions.sort_by_key(|txn| txn.txn_date);
+
+        self.get_transactions(&txn_date)
+            .into_iter()
+            .rev()
+            .find(|txn| txn.txn_type == txn_type && txn.txn_date == txn_date)
+    }
+
+    // draws, payments and interest posted at each statement close through as_of. Interest is
+    // accrued by the day with the same daily rate as a Loan with daily compounding.
+    pub fn get_transactions(&self, &as_of: &NaiveDate) -> Vec<CreditTransaction> {
+        let mut log: Vec<CreditTransaction> = Vec::new();
+        let mut balance = 0.;
+        let mut accrued = 0.;
+        let mut accrued_from = self.open_date;
+        let mut cycle = 1.;
+        let mut close_date = self.add_months(cycle);
+
+        let accrue = |balance: f64, begin_date: &NaiveDate, end_date: &NaiveDate| -> f64 {
+            balance
+                * get_period_interest_rate(
+                    &self.annual_rate,
+                    &PmtSchedule::Monthly,
+                    &Compounding::Daily,
+                    begin_date,
+                    end_date,
+                )
+        };
+
+        for txn in self.transactions.iter().filter(|txn| txn.txn_date <= as_of) {
+            // post interest for the statements that close before the transaction
+            while close_date < txn.txn_date {
+                accrued += accrue(balance, &accrued_from, &close_date);
+                balance = self.post_interest(&mut log, &close_date, &mut accrued, balance);
+                accrued_from = close_date;
+                cycle += 1.;
+                close_date = self.add_months(cycle);
+            }
+
+            accrued += accrue(balance, &accrued_from, &txn.txn_date);
+            accrued_from = txn.txn_date;
+            balance = match txn.txn_type {
+                TransactionType::Payment => round(balance - txn.amount, self.dec_places),
+                _ => round(balance + txn.amount, self.dec_places),
+            };
+            log.push(CreditTransaction { balance, ..*txn });
+        }
+
+        while close_date <= as_of {
+            accrued += accrue(balance, &accrued_from, &close_date);
+            balance = self.post_interest(&mut log, &close_date, &mut accrued, balance);
+            accrued_from = close_date;
+            cycle += 1.;
+            close_date = self.add_months(cycle);
+        }
+        log
+    }
+
+    // add the accrued interest to the balance when a statement closes
+    fn post_interest(
+        &self,
+        log: &mut Vec<CreditTransaction>,
+        &close_date: &NaiveDate,
+        accrued: &mut f64,
+        balance: f64,
+    ) -> f64 {
+        let interest = round(*accrued, self.dec_places);
+        *accrued = 0.;
+        if interest <= 0. {
+            return balance;
+        }
+        let balance = round(balance + interest, self.dec_places);
+        log.push(CreditTransaction {
+            txn_date: close_date,
+            txn_type: TransactionType::Interest,
+            amount: interest,
+            balance,
+        });
+        balance
+    }
+
+    // balance as of a date, including interest posted at statement closes up to then
+    pub fn get_balance(&self, &as_of: &NaiveDate) -> f64 {
+        self.get_transactions(&as_of)
+            .last()
+            .map_or(0., |txn| txn.balance)
+    }
+
+    // credit available to draw as of a date: none once the draw period ends
+    pub fn get_available_credit(&self, &as_of: &NaiveDate) -> f64 {
+        if as_of > self.get_draw_end_date() {
+            return 0.;
+        }
+        round(
+            (self.credit_limit - self.get_balance(&as_of)).max(0.),
+            self.dec_places,
+        )
+    }
+
+    // monthly payment that amortizes the balance at the end of the draw period over the
+    // repayment period
+    pub fn get_repayment_pmt(&self) -> f64 {
+        let draw_end_date = self.get_draw_end_date();
+        let balance = self.get_balance(&draw_end_date);
+        if balance <= 0. {
+            return 0.;
+        }
+        let loan = Loan::new(
+            balance,
+            self.repay_years,
+            self.annual_rate,
+            PmtSchedule::Monthly,
+            Compounding::Daily,
+            draw_end_date,
+            draw_end_date.checked_add_months(Months::new(1)).unwrap(),
+            self.dec_places,
+        );
+        *loan.get_pmt_amount()
+    }
+
+    // monthly statements for the cycles that close on or before as_of
+    pub fn get_statements(&self, &as_of: &NaiveDate) -> Vec<CreditStatement> {
+        let log = self.get_transactions(&as_of);
+        let draw_end_date = self.get_draw_end_date();
+        let repayment_pmt = if as_of > draw_end_date {
+            self.get_repayment_pmt()
+        } else {
+            0.
+        };
+
+        let mut statements = Vec::new();
+        let mut begin_date = self.open_date;
+        let mut begin_balance = 0.;
+        let mut cycle = 1.;
+        let mut close_date = self.add_months(cycle);
+        while close_date <= as_of {
+            let mut statement = CreditStatement {
+                begin_date,
+                close_date,
+                begin_balance,
+                end_balance: begin_balance,
+                due_date: close_date + Days::new(PMT_DUE_DAYS),
+                ..Default::default()
+            };
+            for txn in log
+                .iter()
+                .filter(|txn| txn.txn_date > begin_date && txn.txn_date <= close_date)
+            {
+                match txn.txn_type {
+                    TransactionType::Draw => statement.draws += txn.amount,
+                    TransactionType::Payment => statement.pmts += txn.amount,
+                    TransactionType::Interest => statement.interest += txn.amount,
+                }
+                statement.end_balance = txn.balance;
+            }
+            statement.draws = round(statement.draws, self.dec_places);
+            statement.pmts = round(statement.pmts, self.dec_places);
+            statement.available_credit = if close_date < draw_end_date {
+                round(
+                    (self.credit_limit - statement.end_balance).max(0.),
+                    self.dec_places,
+                )
+            } else {
+                0.
+            };
+
+            let min_pmt = match (close_date < draw_end_date, self.min_pmt_rule) {
+                (true, MinPmtRule::InterestOnly) => statement.interest,
+                (true, MinPmtRule::PctOfBalance(pct)) => {
+                    (statement.end_balance * pct / 100.).max(statement.interest)
+                }
+                (false, _) => repayment_pmt,
+            };
+            statement.min_pmt = round(min_pmt.min(statement.end_balance), self.dec_places);
+            trace!("statement {}", statement);
+
+            statements.push(statement);
+            begin_date = close_date;
+            begin_balance = statement.end_balance;
+            cycle += 1.;
+            close_date = self.add_months(cycle);
+        }
+        statements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CreditLine, MinPmtRule, TransactionType};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn date(yr: i32, mon: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yr, mon, day).unwrap()
+    }
+
+    fn new_line(min_pmt_rule: MinPmtRule) -> CreditLine {
+        CreditLine::new(100000., 8., date(2024, 1, 1), 10., 20., min_pmt_rule, 2.)
+    }
+
+    #[test]
+    fn test_draws_and_pmts() {
+        let mut line = new_line(MinPmtRule::InterestOnly);
+        assert_eq!(line.get_draw_end_date(), date(2034, 1, 1));
+        assert_eq!(line.get_maturity_date(), date(2054, 1, 1));
+
+        // construction draws in stages
+        let draw = line.draw(date(2024, 1, 15), 40000.).unwrap();
+        assert_eq!(draw.balance, 40000.);
+        line.draw(date(2024, 3, 10), 30000.).unwrap();
+        assert_eq!(line.draw(date(2024, 4, 1), 40000.), None);
+        assert_eq!(line.draw(date(2034, 2, 1), 1000.), None);
+        assert_eq!(line.pay(date(2024, 4, 1), 100000.), None);
+
+        let pmt = line.pay(date(2024, 4, 20), 10000.).unwrap();
+        assert_eq!(pmt.txn_type, TransactionType::Payment);
+
+        let log = line.get_transactions(&date(2024, 5, 1));
+        let interest: Vec<f64> = log
+            .iter()
+            .filter(|txn| txn.txn_type == TransactionType::Interest)
+            .map(|txn| txn.amount)
+            .collect();
+        // 17 days on $40,000 compounded daily at 8% / 365
+        assert_eq!(interest[0], 149.3);
+        assert_eq!(interest.len(), 4);
+        assert_eq!(
+            line.get_balance(&date(2024, 5, 1)),
+            log.last().unwrap().balance
+        );
+        assert_eq!(
+            line.get_available_credit(&date(2024, 5, 1)),
+            ((100000. - log.last().unwrap().balance) * 100.).round() / 100.
+        );
+    }
+
+    #[test]
+    fn test_statements() {
+        let mut line = new_line(MinPmtRule::PctOfBalance(1.));
+        line.draw(date(2024, 1, 15), 50000.).unwrap();
+        line.pay(date(2024, 2, 20), 500.).unwrap();
+
+        let statements = line.get_statements(&date(2024, 3, 15));
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].draws, 50000.);
+        assert_eq!(statements[0].end_balance, 50000. + statements[0].interest);
+        assert_eq!(statements[0].due_date, date(2024, 2, 26));
+        assert_eq!(
+            statements[0].min_pmt,
+            (statements[0].end_balance * 0.01 * 100.).round() / 100.
+        );
+        assert_eq!(statements[1].begin_balance, statements[0].end_balance);
+        assert_eq!(statements[1].pmts, 500.);
+        assert_eq!(
+            statements[1].end_balance,
+            ((statements[1].begin_balance - 500. + statements[1].interest) * 100.).round() / 100.
+        );
+
+        let interest_only = new_line(MinPmtRule::InterestOnly);
+        assert_eq!(
+            interest_only.get_statements(&date(2024, 3, 15))[0].min_pmt,
+            0.
+        );
+    }
+
+    #[test]
+    fn test_repayment_period() {
+        let mut line = new_line(MinPmtRule::InterestOnly);
+        line.draw(date(2024, 1, 15), 50000.).unwrap();
+
+        // interest is capitalized through the draw period, then the balance amortizes
+        let balance = line.get_balance(&line.get_draw_end_date());
+        assert!(balance > 100000.);
+        let repayment_pmt = line.get_repayment_pmt();
+        assert!(repayment_pmt > balance * 0.08 / 12.);
+
+        let statements = line.get_statements(&date(2034, 3, 1));
+        assert_eq!(statements.len(), 122);
+        assert_eq!(statements[121].min_pmt, repayment_pmt);
+        assert_eq!(statements[121].available_credit, 0.);
+        assert!(statements[118].min_pmt < repayment_pmt);
+    }
+}
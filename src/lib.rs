@@ -1 +1,10 @@
-pub mod loan;
\ No newline at end of file
+pub mod calendar;
+pub mod cashflow;
+pub mod construction;
+pub mod credit_card;
+pub mod credit_line;
+pub mod loan;
+pub mod portfolio;
+pub mod rates;
+pub mod refinance;
+pub mod student_loan;
@@ -1,27 +1,220 @@
 #![allow(unused_imports)]
+use crate::calendar::{adjust_date, BusinessDayConvention, HolidayCalendar};
+use crate::rates::get_period_rate;
 use chrono::{Datelike, NaiveDate};
 use log::{info, trace, warn};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt};
 
+mod canadian;
+mod custom;
+mod delinquency;
+mod escrow;
+mod fees;
+mod forbearance;
+mod interest_statement;
+mod pmi;
+mod prepayment;
+mod recast;
+mod returns;
+mod summary;
+mod valuation;
+mod waterfall;
+pub use canadian::*;
+pub use custom::*;
+pub use delinquency::*;
+pub use escrow::*;
+pub use fees::*;
+pub use forbearance::*;
+pub use interest_statement::*;
+pub use pmi::*;
+pub use prepayment::*;
+pub use recast::*;
+pub use summary::*;
+pub use valuation::*;
+pub use waterfall::*;
+
 #[derive(Clone, Copy, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PmtSchedule {
     Weekly,
     Biweekly,
+    AcceleratedWeekly,   // a quarter of the monthly payment every week
+    AcceleratedBiweekly, // half the monthly payment every two weeks
     SemiMonthly,
     #[default]
     Monthly,
     Quarterly,
     SemiAnnually,
     Annually,
+    Custom, // payments on the dates or in the months of the loan's CustomSchedule
 }
 
 impl fmt::Display for PmtSchedule {
-    #[allow(clippy::recursive_format_impl)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self)
+        let name = match self {
+            PmtSchedule::Weekly => "weekly",
+            PmtSchedule::Biweekly => "biweekly",
+            PmtSchedule::AcceleratedWeekly => "accelerated weekly",
+            PmtSchedule::AcceleratedBiweekly => "accelerated biweekly",
+            PmtSchedule::SemiMonthly => "semi-monthly",
+            PmtSchedule::Monthly => "monthly",
+            PmtSchedule::Quarterly => "quarterly",
+            PmtSchedule::SemiAnnually => "semi-annual",
+            PmtSchedule::Annually => "annual",
+            PmtSchedule::Custom => "custom",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// the two days of the month semi-monthly payments fall on. A day past the end of a month falls
+// on its last day, so 31 pays on the last day of every month.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SemiMonthlyDays {
+    pub first_day: u32,
+    pub second_day: u32,
+}
+
+impl Default for SemiMonthlyDays {
+    fn default() -> Self {
+        Self {
+            first_day: 1,
+            second_day: 15,
+        }
+    }
+}
+
+impl SemiMonthlyDays {
+    pub fn new(first_day: u32, second_day: u32) -> Self {
+        let first_day = first_day.clamp(1, 31);
+        let second_day = second_day.clamp(1, 31);
+        Self {
+            first_day: first_day.min(second_day),
+            second_day: first_day.max(second_day),
+        }
+    }
+
+    // infer the payment days from the first payment date: the 1st and 15th, the 15th and the
+    // end of the month, or the first payment day and the day 15 days before or after it
+    pub fn from_first_pmt_date(&first_pmt_date: &NaiveDate) -> Self {
+        let day = first_pmt_date.day();
+        if day == 1 || day == 15 {
+            Self::default()
+        } else if day == get_days_in_month(&first_pmt_date) {
+            Self::new(15, 31)
+        } else if day < 15 {
+            Self::new(day, day + 15)
+        } else {
+            Self::new(day - 15, day)
+        }
+    }
+
+    // infer the payment days from two consecutive payment dates. Returns None if the dates are
+    // not a half month apart.
+    pub fn from_pmt_dates(&first_date: &NaiveDate, &second_date: &NaiveDate) -> Option<Self> {
+        let months_apart = (second_date.year() - first_date.year()) * 12
+            + second_date.month() as i32
+            - first_date.month() as i32;
+        let pmt_day = |date: &NaiveDate| {
+            if date.day() == get_days_in_month(date) {
+                31
+            } else {
+                date.day()
+            }
+        };
+        let (first_day, second_day) = (pmt_day(&first_date), pmt_day(&second_date));
+
+        match months_apart {
+            0 if first_day < second_day => Some(Self::new(first_day, second_day)),
+            1 if second_day < first_day => Some(Self::new(second_day, first_day)),
+            _ => None,
+        }
+    }
+
+    // the next payment day after begin_date
+    fn get_next_date(&self, &begin_date: &NaiveDate) -> NaiveDate {
+        let day_in = |date: &NaiveDate, day: u32| {
+            let day = day.min(get_days_in_month(date));
+            NaiveDate::from_ymd_opt(date.year(), date.month(), day).unwrap()
+        };
+
+        for date in [
+            day_in(&begin_date, self.first_day),
+            day_in(&begin_date, self.second_day),
+        ] {
+            if date > begin_date {
+                return date;
+            }
+        }
+        let next_month = begin_date
+            .with_day(1)
+            .and_then(|date| date.checked_add_months(chrono::Months::new(1)))
+            .unwrap();
+        day_in(&next_month, self.first_day)
+    }
+}
+
+// how payment dates fall within the month. Monthly, quarterly, semi-annual and annual payments
+// are due on pmt_day, or on the last day of the month when it is shorter or end_of_month is set.
+// A due date on a weekend or holiday moves to a business day under business_day_convention.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PmtDateRule {
+    pub pmt_day: u32,
+    pub end_of_month: bool,
+    pub semi_monthly_days: SemiMonthlyDays,
+    pub business_day_convention: BusinessDayConvention,
+    pub holiday_calendar: HolidayCalendar,
+    pub custom_schedule: CustomSchedule,
+}
+
+impl Default for PmtDateRule {
+    fn default() -> Self {
+        Self {
+            pmt_day: 1,
+            end_of_month: false,
+            semi_monthly_days: SemiMonthlyDays::default(),
+            business_day_convention: BusinessDayConvention::Unadjusted,
+            holiday_calendar: HolidayCalendar::None,
+            custom_schedule: CustomSchedule::default(),
+        }
+    }
+}
+
+impl PmtDateRule {
+    // anchor payments to the day of the first payment date
+    pub fn from_first_pmt_date(&first_pmt_date: &NaiveDate) -> Self {
+        Self {
+            pmt_day: first_pmt_date.day(),
+            end_of_month: false,
+            semi_monthly_days: SemiMonthlyDays::from_first_pmt_date(&first_pmt_date),
+            ..Default::default()
+        }
+    }
+
+    // the business day a payment nominally due on pmt_date is due
+    pub fn adjust(&self, &pmt_date: &NaiveDate) -> NaiveDate {
+        adjust_date(
+            &pmt_date,
+            &self.business_day_convention,
+            &self.holiday_calendar,
+        )
+    }
+
+    // the payment day months after begin_date
+    fn add_months(&self, &begin_date: &NaiveDate, months: u32) -> Option<NaiveDate> {
+        let month = begin_date
+            .with_day(1)?
+            .checked_add_months(chrono::Months::new(months))?;
+        let days_in_month = get_days_in_month(&month);
+        if self.end_of_month {
+            month.with_day(days_in_month)
+        } else {
+            month.with_day(self.pmt_day.clamp(1, days_in_month))
+        }
     }
 }
 
@@ -34,16 +227,24 @@ pub enum Compounding {
     Quarterly,
     SemiAnnually,
     Annually,
+    Continuous,
+    Simple, // simple interest accrued by the day, as on U.S. federal student loans
 }
 
 #[derive(Clone, Copy, PartialEq, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LoanPayment {
     pub pmt_number: i32,
-    pub pmt_date: NaiveDate,
+    pub pmt_date: NaiveDate,         // due date, adjusted to a business day
+    pub pmt_nominal_date: NaiveDate, // due date before business day adjustment
     pub pmt_amount: f64,
     pub pmt_interest_paid: f64,
     pub pmt_end_balance: f64,
+    pub pmt_fees_paid: f64,
+    pub pmt_escrow_paid: f64,
+    pub pmt_pmi_paid: f64,
+    pub pmt_principal_paid: f64,
+    pub pmt_extra_principal: f64,
 }
 
 impl LoanPayment {
@@ -57,9 +258,15 @@ impl LoanPayment {
         Self {
             pmt_number,
             pmt_date,
+            pmt_nominal_date: pmt_date,
             pmt_amount,
             pmt_interest_paid,
             pmt_end_balance,
+            pmt_fees_paid: 0.,
+            pmt_escrow_paid: 0.,
+            pmt_pmi_paid: 0.,
+            pmt_principal_paid: pmt_amount - pmt_interest_paid,
+            pmt_extra_principal: 0.,
         }
     }
 }
@@ -89,9 +296,23 @@ pub struct Loan {
     pub loan_date: NaiveDate,
     pub first_pmt_date: NaiveDate,
     pub dec_places: f64,
+    pmt_date_rule: PmtDateRule,
     pmt_amount: f64,
     scheduled_pmts: Vec<LoanPayment>,
     actual_pmts: Vec<LoanPayment>,
+    fees: Vec<LoanFee>,
+    pmt_waterfall: Vec<PmtBucket>,
+    grace_days: i64,
+    late_fee_rule: LateFeeRule,
+    late_fees: Vec<LateFee>,
+    escrow: Option<Escrow>,
+    pmi: Option<Pmi>,
+    prepayment_plan: PrepaymentPlan,
+    recast_dates: Vec<NaiveDate>,
+    forbearances: Vec<Forbearance>,
+    // interest deferred by each forbearance: the index of the first payment after it, the
+    // amount and how it is repaid
+    deferred_interest: Vec<(usize, f64, ForbearanceRepayment)>,
 }
 
 impl Loan {
@@ -105,15 +326,7 @@ impl Loan {
         first_pmt_date: NaiveDate,
         dec_places: f64,
     ) -> Self {
-        let pmt_amount = get_pmt_amount(
-            &principal,
-            &term,
-            &annual_rate,
-            &pmt_schedule,
-            &compound_type,
-            &dec_places,
-        );
-        Self {
+        let mut loan = Self {
             principal,
             term,
             annual_rate,
@@ -122,21 +335,152 @@ impl Loan {
             loan_date,
             first_pmt_date,
             dec_places,
-            pmt_amount,
-            scheduled_pmts: add_scheduled_pmts(
-                &principal,
-                &loan_date,
-                &first_pmt_date,
-                &annual_rate,
-                &pmt_schedule,
-                &compound_type,
-                &dec_places,
-                pmt_amount,
-            ),
+            pmt_date_rule: PmtDateRule::from_first_pmt_date(&first_pmt_date),
+            pmt_amount: 0.,
+            scheduled_pmts: Vec::new(),
             actual_pmts: Vec::new(),
+            fees: Vec::new(),
+            pmt_waterfall: default_pmt_waterfall(),
+            grace_days: 0,
+            late_fee_rule: LateFeeRule::None,
+            late_fees: Vec::new(),
+            escrow: None,
+            pmi: None,
+            prepayment_plan: PrepaymentPlan::default(),
+            recast_dates: Vec::new(),
+            forbearances: Vec::new(),
+            deferred_interest: Vec::new(),
+        };
+        loan.recalc_schedule();
+        loan
+    }
+
+    // recalculate the payment amount and the scheduled payments from the current loan terms
+    fn recalc_schedule(&mut self) {
+        let note_amount = self.get_note_amount();
+        self.pmt_amount = match self.get_level_pmt_dates(&0) {
+            Some(pmt_dates) => get_level_pmt_amount(
+                &note_amount,
+                &self.loan_date,
+                &pmt_dates,
+                &self.annual_rate,
+                &self.compound_type,
+                &self.dec_places,
+            ),
+            None => get_pmt_amount(
+                &note_amount,
+                &self.term,
+                &self.annual_rate,
+                &self.pmt_schedule,
+                &self.compound_type,
+                &self.dec_places,
+            ),
+        };
+        self.scheduled_pmts = add_scheduled_pmts(
+            &note_amount,
+            &self.loan_date,
+            &self.first_pmt_date,
+            &self.annual_rate,
+            &self.pmt_schedule,
+            &self.pmt_date_rule,
+            &self.compound_type,
+            &self.dec_places,
+            self.pmt_amount,
+            &self.prepayment_plan,
+        );
+        self.apply_schedule_changes();
+    }
+
+    // apply recasts and forbearances in date order, so each continues from the payment in effect
+    // when it starts. A forbearance starting on or before a recast date comes first. Deferred
+    // interest is added once the schedule is final, since each change rebuilds the payments
+    // after it.
+    fn apply_schedule_changes(&mut self) {
+        self.deferred_interest.clear();
+        let mut recast_dates = self.recast_dates.clone().into_iter().peekable();
+        let mut forbearances = self.forbearances.clone().into_iter().peekable();
+        let mut forborne_pmts = 0;
+        loop {
+            let forbearance_date = forbearances.peek().map(|forbearance| {
+                self.scheduled_pmts
+                    .get((forbearance.start_pmt.max(1) - 1) as usize)
+                    .map_or(NaiveDate::MAX, |pmt| pmt.pmt_date)
+            });
+            match (recast_dates.peek().copied(), forbearance_date) {
+                (None, None) => break,
+                (Some(recast_date), Some(start_date)) if recast_date < start_date => {
+                    self.apply_recast(&recast_date, &forborne_pmts);
+                    recast_dates.next();
+                }
+                (Some(recast_date), None) => {
+                    self.apply_recast(&recast_date, &forborne_pmts);
+                    recast_dates.next();
+                }
+                (_, Some(_)) => {
+                    let forbearance = forbearances.next().unwrap();
+                    forborne_pmts += self.apply_forbearance(&forbearance);
+                }
+            }
+        }
+        for (first, deferred, repayment) in self.deferred_interest.clone() {
+            self.repay_deferred(&first, &deferred, &repayment);
         }
     }
 
+    // payment dates are anchored to the first payment date unless a rule is set
+    pub fn set_pmt_date_rule(&mut self, pmt_date_rule: PmtDateRule) {
+        self.pmt_date_rule = pmt_date_rule;
+        self.recalc_schedule();
+        self.recalc_actual_pmts();
+    }
+
+    // move due dates falling on weekends and holidays to business days
+    pub fn set_business_day_adjustment(
+        &mut self,
+        business_day_convention: BusinessDayConvention,
+        holiday_calendar: HolidayCalendar,
+    ) {
+        self.set_pmt_date_rule(PmtDateRule {
+            business_day_convention,
+            holiday_calendar,
+            ..self.pmt_date_rule.clone()
+        });
+    }
+
+    pub fn get_pmt_date_rule(&self) -> &PmtDateRule {
+        &self.pmt_date_rule
+    }
+
+    // semi-monthly payment days, inferred from the first payment date unless set
+    pub fn set_semi_monthly_days(&mut self, semi_monthly_days: SemiMonthlyDays) {
+        self.set_pmt_date_rule(PmtDateRule {
+            semi_monthly_days,
+            ..self.pmt_date_rule.clone()
+        });
+    }
+
+    // infer the semi-monthly payment days from the first payment date and the payment after it.
+    // Returns None, leaving the days unchanged, if the dates are not a half month apart.
+    pub fn set_second_pmt_date(&mut self, second_pmt_date: NaiveDate) -> Option<SemiMonthlyDays> {
+        match SemiMonthlyDays::from_pmt_dates(&self.first_pmt_date, &second_pmt_date) {
+            Some(semi_monthly_days) => {
+                self.set_semi_monthly_days(semi_monthly_days);
+                Some(semi_monthly_days)
+            }
+            None => {
+                warn!(
+                    "second payment date {} does not follow first payment date {} by a half month",
+                    second_pmt_date, self.first_pmt_date
+                );
+                None
+            }
+        }
+    }
+
+    pub fn get_semi_monthly_days(&self) -> &SemiMonthlyDays {
+        &self.pmt_date_rule.semi_monthly_days
+    }
+
     pub fn get_pmt_amount(&self) -> &f64 {
         &self.pmt_amount
     }
@@ -161,14 +505,122 @@ impl Loan {
         }
     }
 
+    // loan balance as of a date: the balance after the last actual or projected payment on or
+    // before the date
+    pub fn get_balance(&self, &as_of: &NaiveDate) -> f64 {
+        self.get_balance_from(&self.get_projected_pmts(), &as_of)
+    }
+
+    // the balance after the last of pmts on or before as_of, for callers that already have the
+    // projected payments
+    pub(crate) fn get_balance_from(&self, pmts: &[LoanPayment], &as_of: &NaiveDate) -> f64 {
+        pmts.iter()
+            .rev()
+            .find(|pmt| pmt.pmt_date <= as_of)
+            .map_or(self.get_note_amount(), |pmt| pmt.pmt_end_balance)
+    }
+
+    pub fn get_scheduled_pmts(&self) -> &[LoanPayment] {
+        &self.scheduled_pmts
+    }
+
+    // actual payments followed by the remaining scheduled payments, re-projected from the balance
+    // after the last actual payment
+    pub fn get_projected_pmts(&self) -> Vec<LoanPayment> {
+        let last_actual = match self.actual_pmts.last() {
+            Some(pmt) => *pmt,
+            None => return self.scheduled_pmts.clone(),
+        };
+        let mut pmts = self.actual_pmts.clone();
+        if last_actual.pmt_end_balance <= 0. {
+            return pmts;
+        }
+
+        let pmts_made = self
+            .actual_pmts
+            .iter()
+            .map(|pmt| pmt.pmt_number)
+            .max()
+            .unwrap_or_default();
+        let next_pmt_date = self
+            .scheduled_pmts
+            .iter()
+            .find(|pmt| pmt.pmt_number > pmts_made && pmt.pmt_date > last_actual.pmt_date)
+            .map_or(
+                get_next_pmt_date(
+                    &last_actual.pmt_date,
+                    &self.pmt_schedule,
+                    &self.pmt_date_rule,
+                ),
+                |pmt| pmt.pmt_nominal_date,
+            );
+
+        let remaining_pmts = add_scheduled_pmts(
+            &last_actual.pmt_end_balance,
+            &last_actual.pmt_date,
+            &next_pmt_date,
+            &self.annual_rate,
+            &self.pmt_schedule,
+            &self.pmt_date_rule,
+            &self.compound_type,
+            &self.dec_places,
+            self.pmt_amount,
+            &self.prepayment_plan.after_pmt(&pmts_made),
+        );
+        pmts.extend(remaining_pmts.into_iter().map(|mut pmt| {
+            pmt.pmt_number += pmts_made;
+            pmt
+        }));
+        pmts
+    }
+
     pub fn show_amortization(&self) {
         for pmt in &self.scheduled_pmts {
             println!("{}", pmt);
         }
     }
+
+    // record an actual payment against scheduled payment pmt_number. Payments may be entered
+    // out of sequence; interest is calculated from the preceding actual payment (or the loan
+    // date for the first payment).
+    pub fn add_actual_pmt(
+        &mut self,
+        pmt_number: i32,
+        pmt_date: NaiveDate,
+        pmt_amount: f64,
+    ) -> Option<LoanPayment> {
+        if pmt_number < 1 || pmt_number as usize > self.get_pmt_count() || pmt_date < self.loan_date
+        {
+            warn!(
+                "payment number {} on {} is not valid for this loan",
+                pmt_number, pmt_date
+            );
+            return None;
+        }
+
+        self.actual_pmts
+            .push(LoanPayment::new(pmt_number, pmt_date, pmt_amount, 0., 0.));
+        self.recalc_actual_pmts();
+
+        self.actual_pmts
+            .iter()
+            .rev()
+            .find(|pmt| pmt.pmt_number == pmt_number && pmt.pmt_date == pmt_date)
+            .copied()
+    }
+
+    pub fn get_actual_pmts(&self) -> &[LoanPayment] {
+        &self.actual_pmts
+    }
+
+    pub fn show_actual_pmts(&self) {
+        for pmt in &self.actual_pmts {
+            println!("{}", pmt);
+        }
+    }
 }
 
-fn round(amt: f64, dec: f64) -> f64 {
+pub(crate) fn round(amt: f64, dec: f64) -> f64 {
     if amt == 0. {
         0.
     } else {
@@ -184,12 +636,32 @@ fn get_pmt_amount(
     &compound_type: &Compounding, // interest compounding frequency
     &dec_places: &f64,            // calculate to dec_places
 ) -> f64 {
-    let compounding_periods = get_compounding_periods(compound_type);
-    let pmt_count = get_pmt_schedule(pmt_schedule);
+    // accelerated payments are a fraction of the monthly payment, paid weekly or biweekly
+    let monthly_divisor = match pmt_schedule {
+        PmtSchedule::AcceleratedWeekly => 4.,
+        PmtSchedule::AcceleratedBiweekly => 2.,
+        _ => 0.,
+    };
+    if monthly_divisor > 0. {
+        let monthly_pmt = get_pmt_amount(
+            &principal,
+            &term,
+            &annual_rate,
+            &PmtSchedule::Monthly,
+            &compound_type,
+            &dec_places,
+        );
+        return round(monthly_pmt / monthly_divisor, dec_places);
+    }
 
-    let pmt_rate = ((1. + ((annual_rate / 100.) / compounding_periods))
-        .powf(compounding_periods / pmt_count))
-        - 1.0;
+    let pmt_count = match get_pmt_schedule(pmt_schedule) {
+        Some(pmt_count) => pmt_count,
+        None => {
+            warn!("{:?} payments have no fixed frequency", pmt_schedule);
+            return 0.;
+        }
+    };
+    let pmt_rate = get_period_rate(&annual_rate, &compound_type, &pmt_count);
 
     let total_pmts = term * pmt_count;
     let factor = (1. + pmt_rate).powf(total_pmts);
@@ -199,62 +671,71 @@ fn get_pmt_amount(
 }
 
 // calculate a vector of scheduled LoanPayment to add to Loan during New
+#[allow(clippy::too_many_arguments)]
 fn add_scheduled_pmts(
     &principal: &f64,
     &loan_date: &NaiveDate,
     &first_pmt_date: &NaiveDate,
     &annual_rate: &f64,
     &pmt_schedule: &PmtSchedule,
+    pmt_date_rule: &PmtDateRule,
     &compound_type: &Compounding,
     &dec_places: &f64,
     pmt_amount: f64,
+    prepayment_plan: &PrepaymentPlan,
 ) -> Vec<LoanPayment> {
     let mut sched_pmt: Vec<LoanPayment> = Vec::new();
 
     let compounding_periods = get_compounding_periods(compound_type);
-    let pmt_frequency = get_pmt_schedule(pmt_schedule);
 
     let mut end_balance = 1.; // arbitrary value > 0. Will be set by calculation in the loop.
     let mut begin_balance = principal; // beginning balance for the compounding period
     let mut pmt_number = 0; // incremental payment number
     let mut pmt_amt = pmt_amount; // the amount of each payment
     let mut begin_date: NaiveDate = loan_date; // beginning date of the compounding period
-    let mut end_date: NaiveDate = first_pmt_date; // end date of the compounding period
+    let mut nominal_date: NaiveDate = first_pmt_date; // payment due date before adjustment
+    let mut end_date: NaiveDate = pmt_date_rule.adjust(&first_pmt_date); // end date of the compounding period
     let mut period_interest_rate = 0.; // rate applied to the principal to determine interest
     let mut interest; // interest payment
+    let mut extra; // extra principal paid under the prepayment plan
     let mut days; // length of the compounding period in days
     let mut common_rates = HashMap::new(); // HashMap of common compound interest rates
     let daily_rate = (annual_rate / 100.) / compounding_periods;
 
-    if compounding_periods == 365. {
+    if compound_type == Compounding::Daily {
         // create hashmap of period interest rates for common durations (28, 29, 30 and 31 days)
         for i in [28, 29, 30, 31] {
             common_rates.insert(i, (1. + daily_rate).powi(i) - 1.);
         }
-    } else {
+    } else if let Some(pmt_frequency) = get_pmt_schedule(pmt_schedule) {
         // calculate the period interest rate based on payment schedule and compounding type
-        if pmt_frequency == compounding_periods {
-            period_interest_rate = daily_rate;
-        } else {
-            period_interest_rate = (1. + daily_rate).powf(compounding_periods / pmt_frequency) - 1.;
-        }
+        period_interest_rate = get_period_rate(&annual_rate, &compound_type, &pmt_frequency);
     }
 
     while end_balance > 0. && pmt_number < 500 {
         if pmt_number > 0 {
             begin_date = end_date;
-            end_date = get_next_pmt_date(&begin_date, &pmt_schedule);
+            nominal_date = get_next_pmt_date(&nominal_date, &pmt_schedule, pmt_date_rule);
+            end_date = pmt_date_rule.adjust(&nominal_date);
             begin_balance = end_balance;
         }
 
         pmt_number += 1;
 
-        if compounding_periods == 365. {
+        if compound_type == Compounding::Daily {
             days = end_date.signed_duration_since(begin_date).num_days() as i32;
             period_interest_rate = common_rates
                 .get(&days)
                 .copied()
                 .unwrap_or((1. + daily_rate).powi(days) - 1.);
+        } else if pmt_schedule == PmtSchedule::Custom || compound_type == Compounding::Simple {
+            period_interest_rate = get_period_interest_rate(
+                &annual_rate,
+                &pmt_schedule,
+                &compound_type,
+                &begin_date,
+                &end_date,
+            );
         }
         trace!(
             "pmt # {}, period interest rate {}",
@@ -263,11 +744,17 @@ fn add_scheduled_pmts(
         );
 
         interest = begin_balance * period_interest_rate;
+        extra = prepayment_plan.get_extra_pmt(&pmt_number, &begin_date, &end_date);
 
-        if pmt_amt <= begin_balance {
-            end_balance = begin_balance - (pmt_amt - interest);
+        // the last of a list of custom dates pays off the loan
+        let last_pmt = pmt_schedule == PmtSchedule::Custom
+            && pmt_date_rule.get_next_custom_date(&nominal_date).is_none();
+
+        if pmt_amt + extra <= begin_balance && !last_pmt {
+            end_balance = begin_balance - (pmt_amt + extra - interest);
         } else {
-            pmt_amt = begin_balance + interest;
+            extra = extra.min((begin_balance + interest - pmt_amt).max(0.));
+            pmt_amt = begin_balance + interest - extra;
             end_balance = 0.;
         }
         trace!(
@@ -278,75 +765,93 @@ fn add_scheduled_pmts(
             end_balance
         );
 
-        sched_pmt.push(LoanPayment::new(
+        let mut pmt = LoanPayment::new(
             pmt_number,
             end_date,
-            round(pmt_amt, dec_places),
+            round(pmt_amt + extra, dec_places),
             round(interest, dec_places),
             round(end_balance, dec_places),
-        ));
+        );
+        pmt.pmt_nominal_date = nominal_date;
+        pmt.pmt_principal_paid = round(pmt_amt - interest, dec_places);
+        pmt.pmt_extra_principal = round(extra, dec_places);
+        sched_pmt.push(pmt);
     }
     sched_pmt
 }
 
-fn get_compounding_periods(compound_type: Compounding) -> f64 {
+// interest rate for the period between two payment dates. Daily compounding, simple interest and
+// custom schedules use the actual days in the period; otherwise there is a fixed rate per payment
+// period.
+pub(crate) fn get_period_interest_rate(
+    &annual_rate: &f64,
+    &pmt_schedule: &PmtSchedule,
+    &compound_type: &Compounding,
+    &begin_date: &NaiveDate,
+    &end_date: &NaiveDate,
+) -> f64 {
+    if compound_type == Compounding::Daily {
+        let rate = (annual_rate / 100.) / get_compounding_periods(compound_type);
+        let days = end_date.signed_duration_since(begin_date).num_days() as i32;
+        (1. + rate).powi(days) - 1.
+    } else if compound_type == Compounding::Simple {
+        let days = end_date.signed_duration_since(begin_date).num_days() as f64;
+        annual_rate / 100. * days / 365.
+    } else if let Some(pmt_frequency) = get_pmt_schedule(pmt_schedule) {
+        get_period_rate(&annual_rate, &compound_type, &pmt_frequency)
+    } else {
+        let days = end_date.signed_duration_since(begin_date).num_days().max(1) as f64;
+        get_period_rate(&annual_rate, &compound_type, &(365. / days))
+    }
+}
+
+pub(crate) fn get_compounding_periods(compound_type: Compounding) -> f64 {
     match compound_type {
-        Compounding::Daily => 365.,
+        Compounding::Daily | Compounding::Simple => 365.,
         Compounding::Monthly => 12.,
         Compounding::Quarterly => 4.,
         Compounding::SemiAnnually => 2.,
         Compounding::Annually => 1.,
+        Compounding::Continuous => f64::INFINITY,
     }
 }
 
-fn get_pmt_schedule(pmt_schedule: PmtSchedule) -> f64 {
+// payments in a year, or None for custom schedules, which have no fixed frequency.
+// Loan::get_pmts_per_year counts a custom schedule's dates.
+pub(crate) fn get_pmt_schedule(pmt_schedule: PmtSchedule) -> Option<f64> {
     match pmt_schedule {
-        PmtSchedule::Weekly => 52.,
-        PmtSchedule::Biweekly => 26.,
-        PmtSchedule::SemiMonthly => 24.,
-        PmtSchedule::Monthly => 12.,
-        PmtSchedule::Quarterly => 4.,
-        PmtSchedule::SemiAnnually => 2.,
-        PmtSchedule::Annually => 1.,
+        PmtSchedule::Weekly | PmtSchedule::AcceleratedWeekly => Some(52.),
+        PmtSchedule::Biweekly | PmtSchedule::AcceleratedBiweekly => Some(26.),
+        PmtSchedule::SemiMonthly => Some(24.),
+        PmtSchedule::Monthly => Some(12.),
+        PmtSchedule::Quarterly => Some(4.),
+        PmtSchedule::SemiAnnually => Some(2.),
+        PmtSchedule::Annually => Some(1.),
+        PmtSchedule::Custom => None,
     }
 }
 
-fn get_next_pmt_date(&begin_date: &NaiveDate, &pmt_schedule: &PmtSchedule) -> NaiveDate {
-    let day = begin_date.day();
-    let mon = begin_date.month();
-    let yr = begin_date.year();
-    let end_date: Option<NaiveDate>;
-
-    match &pmt_schedule {
-        PmtSchedule::Weekly => {
-            end_date = begin_date.checked_add_days(chrono::Days::new(7));
+pub(crate) fn get_next_pmt_date(
+    &begin_date: &NaiveDate,
+    &pmt_schedule: &PmtSchedule,
+    pmt_date_rule: &PmtDateRule,
+) -> NaiveDate {
+    let end_date = match &pmt_schedule {
+        PmtSchedule::Weekly | PmtSchedule::AcceleratedWeekly => {
+            begin_date.checked_add_days(chrono::Days::new(7))
         }
-        PmtSchedule::Biweekly => {
-            end_date = begin_date.checked_add_days(chrono::Days::new(14));
+        PmtSchedule::Biweekly | PmtSchedule::AcceleratedBiweekly => {
+            begin_date.checked_add_days(chrono::Days::new(14))
         }
-        // semi-monthly payments are presumed to be made on the 1st and 15th of each month
         PmtSchedule::SemiMonthly => {
-            if day == 1 {
-                end_date = NaiveDate::from_ymd_opt(yr, mon, 15);
-            } else if mon == 12 {
-                end_date = NaiveDate::from_ymd_opt(yr + 1, 1, 1);
-            } else {
-                end_date = NaiveDate::from_ymd_opt(yr, mon + 1, 1);
-            }
-        }
-        PmtSchedule::Monthly => {
-            end_date = begin_date.checked_add_months(chrono::Months::new(1));
-        }
-        PmtSchedule::Quarterly => {
-            end_date = begin_date.checked_add_months(chrono::Months::new(3));
-        }
-        PmtSchedule::SemiAnnually => {
-            end_date = begin_date.checked_add_months(chrono::Months::new(6));
+            Some(pmt_date_rule.semi_monthly_days.get_next_date(&begin_date))
         }
-        PmtSchedule::Annually => {
-            end_date = begin_date.checked_add_months(chrono::Months::new(12));
-        }
-    }
+        PmtSchedule::Monthly => pmt_date_rule.add_months(&begin_date, 1),
+        PmtSchedule::Quarterly => pmt_date_rule.add_months(&begin_date, 3),
+        PmtSchedule::SemiAnnually => pmt_date_rule.add_months(&begin_date, 6),
+        PmtSchedule::Annually => pmt_date_rule.add_months(&begin_date, 12),
+        PmtSchedule::Custom => pmt_date_rule.get_next_custom_date(&begin_date),
+    };
 
     match end_date {
         Some(end_date) => end_date,
@@ -354,9 +859,37 @@ fn get_next_pmt_date(&begin_date: &NaiveDate, &pmt_schedule: &PmtSchedule) -> Na
     }
 }
 
+// number of days in the month of date
+fn get_days_in_month(&date: &NaiveDate) -> u32 {
+    let first_of_month = date.with_day(1).unwrap();
+    match first_of_month.checked_add_months(chrono::Months::new(1)) {
+        Some(next_month) => next_month.signed_duration_since(first_of_month).num_days() as u32,
+        None => 31,
+    }
+}
+
+// a monthly loan made March 1, 2024 with its first payment on April 1, shared by the tests of
+// the child modules
+#[cfg(test)]
+pub(crate) fn new_test_loan(principal: f64, term: f64, annual_rate: f64) -> Loan {
+    Loan::new(
+        principal,
+        term,
+        annual_rate,
+        PmtSchedule::Monthly,
+        Compounding::Monthly,
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+        4.,
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_next_pmt_date, get_pmt_amount, Compounding, Loan, LoanPayment, PmtSchedule};
+    use super::{
+        get_next_pmt_date, get_pmt_amount, BusinessDayConvention, Compounding, HolidayCalendar,
+        Loan, LoanPayment, PmtDateRule, PmtSchedule, SemiMonthlyDays,
+    };
     use chrono::NaiveDate;
     use test_log::test;
 
@@ -366,53 +899,115 @@ mod tests {
 
         // base cases
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::Weekly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::Weekly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 2, 8).unwrap()
         );
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::Biweekly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::Biweekly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 2, 15).unwrap()
         );
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::SemiMonthly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::SemiMonthly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 2, 15).unwrap()
         );
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::Monthly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::Monthly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
         );
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::Quarterly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::Quarterly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()
         );
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::SemiAnnually),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::SemiAnnually,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 8, 1).unwrap()
         );
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::Annually),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::Annually,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()
         );
 
         begin_date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::SemiMonthly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::SemiMonthly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
         );
 
         begin_date = NaiveDate::from_ymd_opt(2022, 8, 30).unwrap();
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::SemiAnnually),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::SemiAnnually,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()
         );
 
         begin_date = NaiveDate::from_ymd_opt(2022, 11, 30).unwrap();
         assert_eq!(
-            get_next_pmt_date(&begin_date, &PmtSchedule::Quarterly),
+            get_next_pmt_date(
+                &begin_date,
+                &PmtSchedule::Quarterly,
+                &PmtDateRule::from_first_pmt_date(&begin_date)
+            ),
             NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()
         );
     }
 
+    #[test]
+    fn test_pmt_schedule_display() {
+        assert_eq!(
+            PmtSchedule::AcceleratedBiweekly.to_string(),
+            "accelerated biweekly"
+        );
+        assert_eq!(PmtSchedule::Custom.to_string(), "custom");
+
+        // custom schedules have no fixed frequency to solve a payment with
+        assert_eq!(
+            get_pmt_amount(
+                &200000.,
+                &15.,
+                &7.,
+                &PmtSchedule::Custom,
+                &Compounding::Monthly,
+                &4.
+            ),
+            0.
+        );
+    }
+
     #[test]
     fn test_get_pmt_amount() {
         // exhaustive test of payment calculations
@@ -880,4 +1475,251 @@ mod tests {
         assert_eq!(loan.get_pmt_info(&40), "pmt number 40, date 2027-07-01, payment $1793.1377, interest paid $999.7307, ending balance $171584.8806");
         assert_eq!(loan.get_pmt_info(&180), "pmt number 180, date 2039-03-01, payment $1793.1302, interest paid $10.3395, ending balance $0.0000");
     }
+
+    #[test]
+    fn test_accelerated_biweekly_loan() {
+        let new_loan = |pmt_schedule: PmtSchedule| {
+            Loan::new(
+                200000.,
+                15.,
+                7.,
+                pmt_schedule,
+                Compounding::Monthly,
+                NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+                4.,
+            )
+        };
+        let biweekly = new_loan(PmtSchedule::Biweekly);
+        let accelerated = new_loan(PmtSchedule::AcceleratedBiweekly);
+
+        // half the monthly payment every two weeks pays off the loan early
+        assert_eq!(accelerated.get_pmt_amount(), &898.8283);
+        assert!(biweekly.get_pmt_amount() < accelerated.get_pmt_amount());
+        assert_eq!(biweekly.get_pmt_count(), 390);
+        assert!(accelerated.get_pmt_count() < 390);
+        assert_eq!(
+            accelerated
+                .get_scheduled_pmts()
+                .last()
+                .unwrap()
+                .pmt_end_balance,
+            0.
+        );
+
+        let weekly = new_loan(PmtSchedule::AcceleratedWeekly);
+        assert_eq!(weekly.get_pmt_amount(), &449.4141);
+        assert!(weekly.get_pmt_count() < 2 * accelerated.get_pmt_count());
+    }
+
+    #[test]
+    fn test_semi_monthly_days() {
+        let date = |yr, mon, day| NaiveDate::from_ymd_opt(yr, mon, day).unwrap();
+        let semi_monthly = |semi_monthly_days| PmtDateRule {
+            semi_monthly_days,
+            ..Default::default()
+        };
+        let eom = SemiMonthlyDays::new(15, 31);
+        assert_eq!(
+            get_next_pmt_date(
+                &date(2024, 1, 31),
+                &PmtSchedule::SemiMonthly,
+                &semi_monthly(eom)
+            ),
+            date(2024, 2, 15)
+        );
+        assert_eq!(
+            get_next_pmt_date(
+                &date(2024, 2, 15),
+                &PmtSchedule::SemiMonthly,
+                &semi_monthly(eom)
+            ),
+            date(2024, 2, 29)
+        );
+        assert_eq!(
+            get_next_pmt_date(
+                &date(2024, 12, 31),
+                &PmtSchedule::SemiMonthly,
+                &semi_monthly(eom)
+            ),
+            date(2025, 1, 15)
+        );
+        let days = SemiMonthlyDays::new(20, 5);
+        assert_eq!(days, SemiMonthlyDays::new(5, 20));
+        assert_eq!(
+            get_next_pmt_date(
+                &date(2024, 3, 20),
+                &PmtSchedule::SemiMonthly,
+                &semi_monthly(days)
+            ),
+            date(2024, 4, 5)
+        );
+
+        // infer the days from the first payment date or the first two payment dates
+        assert_eq!(
+            SemiMonthlyDays::from_first_pmt_date(&date(2024, 4, 30)),
+            eom
+        );
+        assert_eq!(
+            SemiMonthlyDays::from_first_pmt_date(&date(2024, 4, 20)),
+            days
+        );
+        assert_eq!(
+            SemiMonthlyDays::from_first_pmt_date(&date(2024, 4, 15)),
+            SemiMonthlyDays::default()
+        );
+        assert_eq!(
+            SemiMonthlyDays::from_pmt_dates(&date(2024, 2, 29), &date(2024, 3, 15)),
+            Some(eom)
+        );
+        assert_eq!(
+            SemiMonthlyDays::from_pmt_dates(&date(2024, 3, 5), &date(2024, 3, 20)),
+            Some(days)
+        );
+        assert_eq!(
+            SemiMonthlyDays::from_pmt_dates(&date(2024, 3, 5), &date(2024, 5, 20)),
+            None
+        );
+
+        // payroll deduction on the 15th and the last day of the month
+        let loan = Loan::new(
+            10000.,
+            2.,
+            6.,
+            PmtSchedule::SemiMonthly,
+            Compounding::Monthly,
+            date(2024, 1, 20),
+            date(2024, 1, 31),
+            2.,
+        );
+        assert_eq!(loan.get_semi_monthly_days(), &eom);
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts.len(), 48);
+        assert_eq!(pmts[1].pmt_date, date(2024, 2, 15));
+        assert_eq!(pmts[2].pmt_date, date(2024, 2, 29));
+        assert_eq!(pmts[4].pmt_date, date(2024, 3, 31));
+        assert_eq!(pmts[47].pmt_date, date(2026, 1, 15));
+
+        // the 10th and the end of the month can only be inferred from the second payment date
+        let mut loan = Loan::new(
+            10000.,
+            2.,
+            6.,
+            PmtSchedule::SemiMonthly,
+            Compounding::Monthly,
+            date(2024, 1, 1),
+            date(2024, 1, 10),
+            2.,
+        );
+        assert_eq!(loan.get_semi_monthly_days(), &SemiMonthlyDays::new(10, 25));
+        assert_eq!(loan.set_second_pmt_date(date(2024, 2, 10)), None);
+        assert_eq!(
+            loan.set_second_pmt_date(date(2024, 1, 31)),
+            Some(SemiMonthlyDays::new(10, 31))
+        );
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[1].pmt_date, date(2024, 1, 31));
+        assert_eq!(pmts[3].pmt_date, date(2024, 2, 29));
+    }
+
+    #[test]
+    fn test_pmt_date_rule() {
+        let date = |yr, mon, day| NaiveDate::from_ymd_opt(yr, mon, day).unwrap();
+        let mut loan = Loan::new(
+            100000.,
+            5.,
+            6.,
+            PmtSchedule::Monthly,
+            Compounding::Monthly,
+            date(2023, 12, 31),
+            date(2024, 1, 31),
+            2.,
+        );
+
+        // payments return to the 31st after a shorter month
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[1].pmt_date, date(2024, 2, 29));
+        assert_eq!(pmts[2].pmt_date, date(2024, 3, 31));
+        assert_eq!(pmts[3].pmt_date, date(2024, 4, 30));
+        assert_eq!(pmts[4].pmt_date, date(2024, 5, 31));
+
+        // a first payment at the end of a short month stays on that day unless set to month end
+        loan.first_pmt_date = date(2024, 2, 29);
+        loan.set_pmt_date_rule(PmtDateRule::from_first_pmt_date(&loan.first_pmt_date));
+        assert_eq!(loan.get_scheduled_pmts()[1].pmt_date, date(2024, 3, 29));
+
+        loan.set_pmt_date_rule(PmtDateRule {
+            end_of_month: true,
+            ..loan.get_pmt_date_rule().clone()
+        });
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[1].pmt_date, date(2024, 3, 31));
+        assert_eq!(pmts[2].pmt_date, date(2024, 4, 30));
+
+        let rule = PmtDateRule::from_first_pmt_date(&date(2024, 1, 31));
+        assert_eq!(
+            get_next_pmt_date(&date(2024, 2, 29), &PmtSchedule::Quarterly, &rule),
+            date(2024, 5, 31)
+        );
+        assert_eq!(
+            get_next_pmt_date(&date(2024, 2, 29), &PmtSchedule::Annually, &rule),
+            date(2025, 2, 28)
+        );
+    }
+
+    #[test]
+    fn test_business_day_adjustment() {
+        let date = |yr, mon, day| NaiveDate::from_ymd_opt(yr, mon, day).unwrap();
+        let unadjusted = Loan::new(
+            100000.,
+            5.,
+            6.,
+            PmtSchedule::Monthly,
+            Compounding::Daily,
+            date(2024, 5, 1),
+            date(2024, 6, 1),
+            4.,
+        );
+        let mut loan = unadjusted.clone();
+        loan.set_business_day_adjustment(
+            BusinessDayConvention::Following,
+            HolidayCalendar::UsFederal,
+        );
+
+        // Saturday, June 1 moves to Monday; Labor Day moves September 2 to the 3rd
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[0].pmt_date, date(2024, 6, 3));
+        assert_eq!(pmts[0].pmt_nominal_date, date(2024, 6, 1));
+        assert_eq!(pmts[1].pmt_date, date(2024, 7, 1));
+        assert_eq!(pmts[3].pmt_date, date(2024, 9, 3));
+        assert_eq!(pmts[3].pmt_nominal_date, date(2024, 9, 1));
+        assert_eq!(pmts[4].pmt_date, date(2024, 10, 1));
+
+        // daily interest accrues to the adjusted dates
+        let sched = unadjusted.get_scheduled_pmts();
+        assert!(pmts[0].pmt_interest_paid > sched[0].pmt_interest_paid);
+        assert!(pmts[1].pmt_interest_paid < sched[1].pmt_interest_paid);
+        assert_eq!(sched[0].pmt_date, sched[0].pmt_nominal_date);
+    }
+
+    #[test]
+    fn test_balance_after_actual_pmt() {
+        let mut loan = super::new_test_loan(200000., 15., 7.);
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1797.6565)
+            .unwrap();
+        assert_eq!(
+            loan.get_balance(&NaiveDate::from_ymd_opt(2024, 4, 15).unwrap()),
+            pmt.pmt_end_balance
+        );
+
+        // later dates pick up the payments projected after the actual payment
+        let balance = loan.get_balance(&NaiveDate::from_ymd_opt(2024, 6, 15).unwrap());
+        assert_eq!(balance, loan.get_projected_pmts()[2].pmt_end_balance);
+        assert!(balance < pmt.pmt_end_balance);
+        assert_eq!(
+            loan.get_balance(&NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+            200000.
+        );
+    }
 }
@@ -0,0 +1,185 @@
+use super::{get_next_pmt_date, Compounding, Loan, PmtDateRule, PmtSchedule};
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// a Canadian fixed-rate mortgage: interest compounds semi-annually, not in advance, whatever the
+// payment frequency. The rate is fixed for the term; the payment is based on the amortization.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CanadianMortgage {
+    pub loan: Loan,
+    pub amortization: f64, // amortization period in years
+    pub term: f64,         // years the rate is fixed before renewal
+}
+
+impl CanadianMortgage {
+    pub fn new(
+        principal: f64,
+        amortization: f64,
+        term: f64,
+        annual_rate: f64,
+        pmt_schedule: PmtSchedule,
+        loan_date: NaiveDate,
+        first_pmt_date: NaiveDate,
+        dec_places: f64,
+    ) -> Self {
+        let loan = Loan::new(
+            principal,
+            amortization,
+            annual_rate,
+            pmt_schedule,
+            Compounding::SemiAnnually,
+            loan_date,
+            first_pmt_date,
+            dec_places,
+        );
+
+        Self {
+            loan,
+            amortization,
+            term,
+        }
+    }
+
+    pub fn get_pmt_amount(&self) -> f64 {
+        *self.loan.get_pmt_amount()
+    }
+
+    // the date the term ends and the mortgage comes up for renewal
+    pub fn get_term_end_date(&self) -> NaiveDate {
+        self.loan
+            .loan_date
+            .checked_add_months(chrono::Months::new((self.term * 12.).round() as u32))
+            .unwrap()
+    }
+
+    pub fn get_balance_at_renewal(&self) -> f64 {
+        self.loan.get_balance(&self.get_term_end_date())
+    }
+
+    // amortization left on the original contract at the end of the term
+    pub fn get_remaining_amortization(&self) -> f64 {
+        (self.amortization - self.term).max(0.)
+    }
+
+    // renew the balance at the end of the term for a new term over the remaining amortization
+    pub fn renew(
+        &self,
+        annual_rate: f64,
+        term: f64,
+        pmt_schedule: PmtSchedule,
+    ) -> CanadianMortgage {
+        self.renew_with_amortization(
+            annual_rate,
+            term,
+            self.get_remaining_amortization(),
+            pmt_schedule,
+        )
+    }
+
+    // renew the balance at the end of the term, re-amortizing over a new amortization period.
+    // The first payment falls one period of the new schedule after renewal.
+    pub fn renew_with_amortization(
+        &self,
+        annual_rate: f64,
+        term: f64,
+        amortization: f64,
+        pmt_schedule: PmtSchedule,
+    ) -> CanadianMortgage {
+        let renewal_date = self.get_term_end_date();
+        CanadianMortgage::new(
+            self.get_balance_at_renewal(),
+            amortization,
+            term.min(amortization),
+            annual_rate,
+            pmt_schedule,
+            renewal_date,
+            get_next_pmt_date(
+                &renewal_date,
+                &pmt_schedule,
+                &PmtDateRule::from_first_pmt_date(&renewal_date),
+            ),
+            self.loan.dec_places,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CanadianMortgage;
+    use crate::loan::PmtSchedule;
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn new_mortgage(pmt_schedule: PmtSchedule) -> CanadianMortgage {
+        CanadianMortgage::new(
+            400000.,
+            25.,
+            5.,
+            6.,
+            pmt_schedule,
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+            2.,
+        )
+    }
+
+    #[test]
+    fn test_canadian_pmts() {
+        // 6% compounded semi-annually over 25 years
+        let monthly = new_mortgage(PmtSchedule::Monthly);
+        assert_eq!(monthly.get_pmt_amount(), 2559.23);
+        assert_eq!(monthly.loan.get_pmt_count(), 300);
+
+        let biweekly = new_mortgage(PmtSchedule::Biweekly);
+        assert_eq!(biweekly.get_pmt_amount(), 1179.61);
+
+        // accelerated biweekly pays half the monthly payment and pays off years sooner
+        let accelerated = new_mortgage(PmtSchedule::AcceleratedBiweekly);
+        assert_eq!(accelerated.get_pmt_amount(), 1279.62);
+        assert!(accelerated.loan.get_pmt_count() < 26 * 22);
+
+        let accelerated = new_mortgage(PmtSchedule::AcceleratedWeekly);
+        assert_eq!(accelerated.get_pmt_amount(), 639.81);
+    }
+
+    #[test]
+    fn test_renewal() {
+        let mortgage = new_mortgage(PmtSchedule::Monthly);
+        assert_eq!(
+            mortgage.get_term_end_date(),
+            NaiveDate::from_ymd_opt(2029, 3, 1).unwrap()
+        );
+        assert_eq!(mortgage.get_remaining_amortization(), 20.);
+
+        let renewed = mortgage.renew(5., 3., PmtSchedule::Monthly);
+        assert_eq!(renewed.loan.principal, mortgage.get_balance_at_renewal());
+        assert_eq!(
+            renewed.loan.first_pmt_date,
+            NaiveDate::from_ymd_opt(2029, 4, 1).unwrap()
+        );
+        assert_eq!(renewed.loan.get_pmt_count(), 240);
+        assert!(renewed.get_pmt_amount() < mortgage.get_pmt_amount());
+
+        let extended = mortgage.renew_with_amortization(5., 5., 25., PmtSchedule::Monthly);
+        assert_eq!(extended.loan.get_pmt_count(), 300);
+        assert!(extended.get_pmt_amount() < renewed.get_pmt_amount());
+    }
+
+    #[test]
+    fn test_renewal_schedule_change() {
+        let mortgage = new_mortgage(PmtSchedule::Monthly);
+        let renewed = mortgage.renew(5., 5., PmtSchedule::AcceleratedBiweekly);
+        assert_eq!(renewed.loan.pmt_schedule, PmtSchedule::AcceleratedBiweekly);
+        assert_eq!(
+            renewed.loan.first_pmt_date,
+            NaiveDate::from_ymd_opt(2029, 3, 15).unwrap()
+        );
+        let pmts = renewed.loan.get_scheduled_pmts();
+        assert_eq!(
+            pmts[1].pmt_date,
+            NaiveDate::from_ymd_opt(2029, 3, 29).unwrap()
+        );
+    }
+}
@@ -0,0 +1,321 @@
+use super::{
+    get_next_pmt_date, get_period_interest_rate, get_pmt_schedule, round, Compounding, Loan,
+    PmtDateRule, PmtSchedule,
+};
+use chrono::{Datelike, NaiveDate};
+use log::{trace, warn};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// payment dates for PmtSchedule::Custom
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CustomSchedule {
+    Dates(Vec<NaiveDate>), // an explicit list of payment dates
+    Months(Vec<u32>),      // a month mask: payments are due in these months (1 - 12) each year
+}
+
+impl Default for CustomSchedule {
+    fn default() -> Self {
+        CustomSchedule::Months((1..=12).collect())
+    }
+}
+
+impl PmtDateRule {
+    // the custom payment date after begin_date, or None when the listed dates run out
+    pub(super) fn get_next_custom_date(&self, &begin_date: &NaiveDate) -> Option<NaiveDate> {
+        match &self.custom_schedule {
+            CustomSchedule::Dates(dates) => dates.iter().find(|date| **date > begin_date).copied(),
+            CustomSchedule::Months(months) => (1..=12)
+                .filter_map(|n| self.add_months(&begin_date, n))
+                .find(|date| months.contains(&date.month())),
+        }
+    }
+}
+
+// solve the level payment that retires principal by the last of pmt_dates
+pub(super) fn get_level_pmt_amount(
+    &principal: &f64,
+    &loan_date: &NaiveDate,
+    pmt_dates: &[NaiveDate],
+    &annual_rate: &f64,
+    &compound_type: &Compounding,
+    &dec_places: &f64,
+) -> f64 {
+    get_first_pmt_amount(
+        &principal,
+        &loan_date,
+        pmt_dates,
+        |_| 1.,
+        &annual_rate,
+        &compound_type,
+        &dec_places,
+    )
+}
+
+// solve the first payment that retires principal by the last of pmt_dates, where payment n
+// (from 1) is the first payment times pmt_growth(n). The ending balance is linear in the payment,
+// so two trial payments are enough to solve for it.
+pub(crate) fn get_first_pmt_amount(
+    &principal: &f64,
+    &loan_date: &NaiveDate,
+    pmt_dates: &[NaiveDate],
+    pmt_growth: impl Fn(usize) -> f64,
+    &annual_rate: &f64,
+    &compound_type: &Compounding,
+    &dec_places: &f64,
+) -> f64 {
+    let end_balance = |pmt_amount: f64| -> f64 {
+        let mut balance = principal;
+        let mut begin_date = loan_date;
+        for (i, pmt_date) in pmt_dates.iter().enumerate() {
+            let rate = get_period_interest_rate(
+                &annual_rate,
+                &PmtSchedule::Custom,
+                &compound_type,
+                &begin_date,
+                pmt_date,
+            );
+            balance = balance * (1. + rate) - pmt_amount * pmt_growth(i + 1);
+            begin_date = *pmt_date;
+        }
+        balance
+    };
+
+    let unpaid = end_balance(0.);
+    let paid_per_dollar = unpaid - end_balance(1.);
+    if paid_per_dollar <= 0. {
+        return round(principal, dec_places);
+    }
+    trace!(
+        "first pmt over {} dates, ending balance unpaid {}",
+        pmt_dates.len(),
+        unpaid
+    );
+    round(unpaid / paid_per_dollar, dec_places)
+}
+
+impl Loan {
+    // payments are due on the custom dates or months when pmt_schedule is PmtSchedule::Custom.
+    // Listed dates and months are kept in order. Returns None, leaving the schedule unchanged,
+    // for an empty list or a month outside 1 - 12.
+    pub fn set_custom_schedule(
+        &mut self,
+        mut custom_schedule: CustomSchedule,
+    ) -> Option<&CustomSchedule> {
+        let valid = match &mut custom_schedule {
+            CustomSchedule::Dates(dates) => {
+                dates.sort();
+                dates.dedup();
+                !dates.is_empty()
+            }
+            CustomSchedule::Months(months) => {
+                months.sort();
+                months.dedup();
+                !months.is_empty() && months.iter().all(|month| (1..=12).contains(month))
+            }
+        };
+        if !valid {
+            warn!("custom schedule {:?} is not valid", custom_schedule);
+            return None;
+        }
+        self.set_pmt_date_rule(PmtDateRule {
+            custom_schedule,
+            ..self.pmt_date_rule.clone()
+        });
+        Some(&self.pmt_date_rule.custom_schedule)
+    }
+
+    pub fn get_custom_schedule(&self) -> &CustomSchedule {
+        &self.pmt_date_rule.custom_schedule
+    }
+
+    // the business-day adjusted due dates of a custom schedule: the first payment date followed by
+    // the listed dates after it, or term years of payments in the masked months plus extra_pmts
+    // more. The schedule always starts on the first payment date, listed or not.
+    pub(super) fn get_custom_pmt_dates(&self, &extra_pmts: &usize) -> Vec<NaiveDate> {
+        let rule = &self.pmt_date_rule;
+        let nominal_dates: Vec<NaiveDate> = match &rule.custom_schedule {
+            CustomSchedule::Dates(dates) => std::iter::once(self.first_pmt_date)
+                .chain(
+                    dates
+                        .iter()
+                        .filter(|date| **date > self.first_pmt_date)
+                        .copied(),
+                )
+                .collect(),
+            CustomSchedule::Months(months) => {
+                let pmt_count = (self.term * months.len() as f64).round() as usize + extra_pmts;
+                std::iter::successors(Some(self.first_pmt_date), |date| {
+                    rule.get_next_custom_date(date)
+                })
+                .take(pmt_count)
+                .collect()
+            }
+        };
+        nominal_dates.iter().map(|date| rule.adjust(date)).collect()
+    }
+
+    // the due dates a level payment is solved over when interest depends on the days in each
+    // period: the custom dates, or term years of regular payments plus extra_pmts more under
+    // simple interest. None when each payment period has the same rate.
+    pub(super) fn get_level_pmt_dates(&self, &extra_pmts: &usize) -> Option<Vec<NaiveDate>> {
+        if self.pmt_schedule == PmtSchedule::Custom {
+            return Some(self.get_custom_pmt_dates(&extra_pmts));
+        }
+        if self.compound_type != Compounding::Simple {
+            return None;
+        }
+        let rule = &self.pmt_date_rule;
+        let pmt_count = (self.term * self.get_pmts_per_year()).round() as usize + extra_pmts;
+        Some(
+            std::iter::successors(Some(self.first_pmt_date), |date| {
+                Some(get_next_pmt_date(date, &self.pmt_schedule, rule))
+            })
+            .take(pmt_count)
+            .map(|date| rule.adjust(&date))
+            .collect(),
+        )
+    }
+
+    // payments in a year, averaged over the life of the loan for custom schedules
+    pub fn get_pmts_per_year(&self) -> f64 {
+        if let Some(pmts_per_year) = get_pmt_schedule(self.pmt_schedule) {
+            return pmts_per_year;
+        }
+        match &self.pmt_date_rule.custom_schedule {
+            CustomSchedule::Months(months) => months.len() as f64,
+            CustomSchedule::Dates(_) => {
+                let pmt_dates = self.get_custom_pmt_dates(&0);
+                let years = pmt_dates.last().map_or(0., |date| {
+                    date.signed_duration_since(self.loan_date).num_days() as f64 / 365.
+                });
+                if years > 0. {
+                    pmt_dates.len() as f64 / years
+                } else {
+                    0.
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CustomSchedule;
+    use crate::loan::{Compounding, Loan, PmtSchedule};
+    use chrono::{Datelike, NaiveDate};
+    use test_log::test;
+
+    fn date(yr: i32, mon: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yr, mon, day).unwrap()
+    }
+
+    fn new_loan() -> Loan {
+        Loan::new(
+            50000.,
+            5.,
+            7.,
+            PmtSchedule::Custom,
+            Compounding::Monthly,
+            date(2024, 8, 1),
+            date(2024, 9, 1),
+            2.,
+        )
+    }
+
+    #[test]
+    fn test_custom_months() {
+        // every month by default is close to a monthly loan; interest accrues by the day
+        let mut loan = new_loan();
+        let mut monthly = new_loan();
+        monthly.pmt_schedule = PmtSchedule::Monthly;
+        monthly.recalc_schedule();
+        assert!((loan.get_pmt_amount() - monthly.get_pmt_amount()).abs() < 0.1);
+        assert_eq!(loan.get_pmt_count(), 60);
+
+        // a school-year loan with no payments in June, July or August
+        loan.set_custom_schedule(CustomSchedule::Months(vec![1, 2, 3, 4, 5, 9, 10, 11, 12]));
+        assert_eq!(loan.get_pmts_per_year(), 9.);
+        assert!(loan.get_pmt_amount() > monthly.get_pmt_amount());
+
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts.len(), 45);
+        assert_eq!(pmts[4].pmt_date, date(2025, 1, 1));
+        assert_eq!(pmts[8].pmt_date, date(2025, 5, 1));
+        assert_eq!(pmts[9].pmt_date, date(2025, 9, 1));
+        assert!(pmts
+            .iter()
+            .all(|pmt| ![6, 7, 8].contains(&pmt.pmt_date.month())));
+        assert_eq!(pmts[44].pmt_end_balance, 0.);
+        assert!((pmts[44].pmt_amount - pmts[0].pmt_amount).abs() < 1.);
+
+        // the payment after the summer accrues four months of interest
+        assert!(pmts[9].pmt_interest_paid > pmts[8].pmt_interest_paid);
+    }
+
+    #[test]
+    fn test_invalid_custom_schedule() {
+        let mut loan = new_loan();
+        let pmt_amount = *loan.get_pmt_amount();
+        assert_eq!(
+            loan.set_custom_schedule(CustomSchedule::Months(vec![])),
+            None
+        );
+        assert_eq!(
+            loan.set_custom_schedule(CustomSchedule::Months(vec![3, 13])),
+            None
+        );
+        assert_eq!(
+            loan.set_custom_schedule(CustomSchedule::Dates(vec![])),
+            None
+        );
+
+        // the loan keeps its schedule
+        assert_eq!(loan.get_custom_schedule(), &CustomSchedule::default());
+        assert_eq!(loan.get_pmt_amount(), &pmt_amount);
+        assert_eq!(loan.get_pmt_count(), 60);
+
+        assert_eq!(
+            loan.set_custom_schedule(CustomSchedule::Months(vec![12, 6, 6])),
+            Some(&CustomSchedule::Months(vec![6, 12]))
+        );
+        assert_eq!(loan.get_pmt_count(), 10);
+    }
+
+    #[test]
+    fn test_custom_dates() {
+        // an agricultural loan paid after each harvest
+        let mut loan = new_loan();
+        let harvests: Vec<NaiveDate> = (2024..2029).map(|yr| date(yr, 10, 15)).collect();
+        loan.first_pmt_date = harvests[0];
+        loan.set_custom_schedule(CustomSchedule::Dates(harvests.clone()));
+
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts.len(), 5);
+        assert_eq!(
+            pmts.iter().map(|pmt| pmt.pmt_date).collect::<Vec<_>>(),
+            harvests
+        );
+        assert_eq!(pmts[4].pmt_end_balance, 0.);
+        assert!((pmts[4].pmt_amount - pmts[1].pmt_amount).abs() < 1.);
+        assert!(loan.get_pmts_per_year() > 1. && loan.get_pmts_per_year() < 1.2);
+
+        // the short first period accrues less interest than a full year
+        assert!(pmts[0].pmt_interest_paid < pmts[1].pmt_interest_paid);
+
+        // an unlisted first payment date is paid, and the level payment covers it too
+        let mut loan = new_loan();
+        let mut unsorted = harvests.clone();
+        unsorted.reverse();
+        loan.set_custom_schedule(CustomSchedule::Dates(unsorted));
+        assert_eq!(loan.get_custom_schedule(), &CustomSchedule::Dates(harvests));
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts.len(), 6);
+        assert_eq!(pmts[0].pmt_date, date(2024, 9, 1));
+        assert_eq!(pmts[1].pmt_date, date(2024, 10, 15));
+        assert_eq!(pmts[5].pmt_end_balance, 0.);
+        assert!((pmts[5].pmt_amount - pmts[0].pmt_amount).abs() < 1.);
+        assert_eq!(pmts[0].pmt_amount, *loan.get_pmt_amount());
+    }
+}
@@ -0,0 +1,280 @@
+use super::{round, Loan};
+use chrono::NaiveDate;
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LateFeeRule {
+    #[default]
+    None,
+    Flat(f64),     // fixed dollar amount
+    PctOfPmt(f64), // percent of the scheduled payment (i.e., 5.0)
+}
+
+// a late fee assessed against a scheduled payment
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LateFee {
+    pub pmt_number: i32,
+    pub due_date: NaiveDate,
+    pub assessed_date: NaiveDate,
+    pub fee_amount: f64,
+}
+
+impl fmt::Display for LateFee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pmt number {}, due {}, assessed {}, late fee ${:.2}",
+            self.pmt_number, self.due_date, self.assessed_date, self.fee_amount
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DelinquencyBucket {
+    #[default]
+    Current,
+    PastDue,    // 1 - 29 days past due
+    Days30,     // 30 - 59 days past due
+    Days60,     // 60 - 89 days past due
+    Days90Plus, // 90 or more days past due
+}
+
+impl DelinquencyBucket {
+    pub fn from_days(days_past_due: i64) -> Self {
+        match days_past_due {
+            i64::MIN..=0 => DelinquencyBucket::Current,
+            1..=29 => DelinquencyBucket::PastDue,
+            30..=59 => DelinquencyBucket::Days30,
+            60..=89 => DelinquencyBucket::Days60,
+            _ => DelinquencyBucket::Days90Plus,
+        }
+    }
+}
+
+// delinquency status of a loan as of a date. Unpaid scheduled amounts are grouped by how long
+// they have been past due.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DelinquencyReport {
+    pub as_of: NaiveDate,
+    pub days_past_due: i64,
+    pub bucket: DelinquencyBucket,
+    pub due_under_30: f64,
+    pub due_30_59: f64,
+    pub due_60_89: f64,
+    pub due_90_plus: f64,
+    pub late_fees_assessed: f64,
+}
+
+impl DelinquencyReport {
+    pub fn get_total_past_due(&self) -> f64 {
+        self.due_under_30 + self.due_30_59 + self.due_60_89 + self.due_90_plus
+    }
+}
+
+impl fmt::Display for DelinquencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "as of {}, days past due {}, status {:?}",
+            self.as_of, self.days_past_due, self.bucket
+        )?;
+        writeln!(f, "  1-29 days   ${:.2}", self.due_under_30)?;
+        writeln!(f, "  30-59 days  ${:.2}", self.due_30_59)?;
+        writeln!(f, "  60-89 days  ${:.2}", self.due_60_89)?;
+        writeln!(f, "  90+ days    ${:.2}", self.due_90_plus)?;
+        write!(f, "  late fees   ${:.2}", self.late_fees_assessed)
+    }
+}
+
+impl Loan {
+    // grace_days is the number of days after the due date a payment may arrive without a late fee
+    pub fn set_late_fee_terms(&mut self, grace_days: i64, late_fee_rule: LateFeeRule) {
+        self.grace_days = grace_days;
+        self.late_fee_rule = late_fee_rule;
+        self.assess_late_fees();
+    }
+
+    pub fn get_late_fees(&self) -> &[LateFee] {
+        &self.late_fees
+    }
+
+    pub fn get_late_fee_total(&self, &as_of: &NaiveDate) -> f64 {
+        round(
+            self.late_fees
+                .iter()
+                .filter(|fee| fee.assessed_date <= as_of)
+                .map(|fee| fee.fee_amount)
+                .sum(),
+            self.dec_places,
+        )
+    }
+
+    // the first actual payment received for a scheduled payment is compared to its due date.
+    // A payment arriving after the grace period is assessed a late fee.
+    pub(super) fn assess_late_fees(&mut self) {
+        self.late_fees.clear();
+        if self.late_fee_rule == LateFeeRule::None {
+            return;
+        }
+
+        for sched in &self.scheduled_pmts {
+            let first_pmt = self
+                .actual_pmts
+                .iter()
+                .find(|pmt| pmt.pmt_number == sched.pmt_number);
+
+            if let Some(pmt) = first_pmt {
+                let days_late = pmt
+                    .pmt_date
+                    .signed_duration_since(sched.pmt_date)
+                    .num_days();
+                if days_late > self.grace_days {
+                    let fee_amount = match self.late_fee_rule {
+                        LateFeeRule::None => 0.,
+                        LateFeeRule::Flat(amt) => amt,
+                        LateFeeRule::PctOfPmt(pct) => sched.pmt_amount * pct / 100.,
+                    };
+                    trace!(
+                        "pmt # {} is {} days late, fee {}",
+                        sched.pmt_number,
+                        days_late,
+                        fee_amount
+                    );
+                    self.late_fees.push(LateFee {
+                        pmt_number: sched.pmt_number,
+                        due_date: sched.pmt_date,
+                        assessed_date: pmt.pmt_date,
+                        fee_amount: round(fee_amount, self.dec_places),
+                    });
+                }
+            }
+        }
+    }
+
+    // number of days the oldest unpaid scheduled payment is past due. Actual payments received
+    // by as_of, less any fees and escrow they paid, are applied to scheduled payments in due
+    // date order.
+    pub fn get_days_past_due(&self, &as_of: &NaiveDate) -> i64 {
+        self.get_delinquency_report(&as_of).days_past_due
+    }
+
+    pub fn get_delinquency_report(&self, &as_of: &NaiveDate) -> DelinquencyReport {
+        let mut report = DelinquencyReport {
+            as_of,
+            late_fees_assessed: self.get_late_fee_total(&as_of),
+            ..Default::default()
+        };
+
+        let mut paid: f64 = self
+            .actual_pmts
+            .iter()
+            .filter(|pmt| pmt.pmt_date <= as_of)
+            .map(|pmt| pmt.pmt_amount - pmt.pmt_fees_paid - pmt.pmt_escrow_paid - pmt.pmt_pmi_paid)
+            .sum();
+
+        for sched in self
+            .scheduled_pmts
+            .iter()
+            .filter(|pmt| pmt.pmt_date < as_of)
+        {
+            let amount_due = sched.pmt_amount - sched.pmt_extra_principal;
+            let unpaid = round(amount_due - paid.min(amount_due), self.dec_places);
+            paid = (paid - amount_due).max(0.);
+            if unpaid <= 0. {
+                continue;
+            }
+
+            let days = as_of.signed_duration_since(sched.pmt_date).num_days();
+            report.days_past_due = report.days_past_due.max(days);
+            match DelinquencyBucket::from_days(days) {
+                DelinquencyBucket::Current | DelinquencyBucket::PastDue => {
+                    report.due_under_30 += unpaid
+                }
+                DelinquencyBucket::Days30 => report.due_30_59 += unpaid,
+                DelinquencyBucket::Days60 => report.due_60_89 += unpaid,
+                DelinquencyBucket::Days90Plus => report.due_90_plus += unpaid,
+            }
+        }
+        report.bucket = DelinquencyBucket::from_days(report.days_past_due);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DelinquencyBucket, LateFeeRule};
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_late_fees() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.set_late_fee_terms(15, LateFeeRule::PctOfPmt(5.));
+
+        // within the grace period
+        loan.add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 16).unwrap(), 1797.6565);
+        assert!(loan.get_late_fees().is_empty());
+
+        // after the grace period
+        loan.add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 5, 17).unwrap(), 1797.6565);
+        assert_eq!(loan.get_late_fees().len(), 1);
+        assert_eq!(loan.get_late_fees()[0].fee_amount, 89.8828);
+
+        loan.set_late_fee_terms(10, LateFeeRule::Flat(35.));
+        assert_eq!(loan.get_late_fees().len(), 2);
+        assert_eq!(
+            loan.get_late_fee_total(&NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()),
+            70.
+        );
+    }
+
+    #[test]
+    fn test_delinquency_report() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1797.6565);
+
+        let report = loan.get_delinquency_report(&NaiveDate::from_ymd_opt(2024, 4, 20).unwrap());
+        assert_eq!(report.days_past_due, 0);
+        assert_eq!(report.bucket, DelinquencyBucket::Current);
+
+        // payments 2, 3 and 4 unpaid
+        let report = loan.get_delinquency_report(&NaiveDate::from_ymd_opt(2024, 7, 5).unwrap());
+        assert_eq!(report.days_past_due, 65);
+        assert_eq!(report.bucket, DelinquencyBucket::Days60);
+        assert_eq!(report.due_under_30, 1797.6565);
+        assert_eq!(report.due_30_59, 1797.6565);
+        assert_eq!(report.due_60_89, 1797.6565);
+        assert_eq!(report.due_90_plus, 0.);
+
+        // a partial payment is applied to the oldest payment due
+        loan.add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 7, 5).unwrap(), 1000.);
+        let report = loan.get_delinquency_report(&NaiveDate::from_ymd_opt(2024, 7, 5).unwrap());
+        assert_eq!(report.days_past_due, 65);
+        assert_eq!(report.due_60_89, 797.6565);
+    }
+
+    #[test]
+    fn test_actual_pmts_out_of_sequence() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), 1797.6565);
+        loan.add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1797.6565);
+
+        let pmts = loan.get_actual_pmts();
+        assert_eq!(pmts[0].pmt_number, 1);
+        assert_eq!(pmts[0].pmt_end_balance, 199369.0102);
+        assert_eq!(pmts[1].pmt_end_balance, 198734.3396);
+
+        assert_eq!(
+            loan.add_actual_pmt(200, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), 100.),
+            None
+        );
+    }
+}
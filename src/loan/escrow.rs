@@ -0,0 +1,299 @@
+use super::{get_next_pmt_date, round, Loan};
+use chrono::{Datelike, NaiveDate};
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// RESPA allows a cushion of up to one sixth (two months) of the annual disbursements
+const ESCROW_CUSHION_MONTHS: f64 = 2.;
+
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Escrow {
+    pub annual_tax: f64,
+    pub annual_insurance: f64,
+    // disbursement dates repeat each year; the annual amount is split evenly across the dates
+    pub tax_dates: Vec<NaiveDate>,
+    pub insurance_dates: Vec<NaiveDate>,
+}
+
+impl Escrow {
+    pub fn new(
+        annual_tax: f64,
+        annual_insurance: f64,
+        tax_dates: Vec<NaiveDate>,
+        insurance_dates: Vec<NaiveDate>,
+    ) -> Self {
+        Self {
+            annual_tax,
+            annual_insurance,
+            tax_dates,
+            insurance_dates,
+        }
+    }
+
+    pub fn get_annual_disbursements(&self) -> f64 {
+        self.annual_tax + self.annual_insurance
+    }
+
+    // disbursements falling after begin_date, up to and including end_date
+    fn get_disbursements(&self, &begin_date: &NaiveDate, &end_date: &NaiveDate) -> f64 {
+        let due = |dates: &Vec<NaiveDate>, annual_amount: f64| -> f64 {
+            if dates.is_empty() {
+                return 0.;
+            }
+            let amount = annual_amount / dates.len() as f64;
+            dates
+                .iter()
+                .flat_map(|date| {
+                    // a February 29 disbursement falls on February 28 in other years
+                    (begin_date.year()..=end_date.year()).map(move |yr| {
+                        date.with_year(yr)
+                            .unwrap_or_else(|| NaiveDate::from_ymd_opt(yr, 2, 28).unwrap())
+                    })
+                })
+                .filter(|date| *date > begin_date && *date <= end_date)
+                .count() as f64
+                * amount
+        };
+        due(&self.tax_dates, self.annual_tax) + due(&self.insurance_dates, self.annual_insurance)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EscrowProjection {
+    pub pmt_date: NaiveDate,
+    pub deposit: f64,
+    pub disbursement: f64,
+    pub end_balance: f64,
+}
+
+impl fmt::Display for EscrowProjection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "date {}, deposit ${:.2}, disbursement ${:.2}, ending balance ${:.2}",
+            self.pmt_date, self.deposit, self.disbursement, self.end_balance
+        )
+    }
+}
+
+// annual escrow analysis: a year of projected deposits and disbursements, and the shortage or
+// surplus against the required cushion
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EscrowAnalysis {
+    pub begin_balance: f64,
+    pub projections: Vec<EscrowProjection>,
+    pub low_balance: f64,
+    pub required_cushion: f64,
+    pub shortage: f64,
+    pub surplus: f64,
+    pub escrow_pmt: f64, // deposit for the coming year, including 1/12th of any shortage
+    pub total_pmt: f64,  // principal and interest plus the new escrow payment
+}
+
+impl fmt::Display for EscrowAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "beginning balance ${:.2}", self.begin_balance)?;
+        for projection in &self.projections {
+            writeln!(f, "{}", projection)?;
+        }
+        write!(
+            f,
+            "low balance ${:.2}, required cushion ${:.2}, shortage ${:.2}, surplus ${:.2}, new escrow payment ${:.2}, new total payment ${:.2}",
+            self.low_balance,
+            self.required_cushion,
+            self.shortage,
+            self.surplus,
+            self.escrow_pmt,
+            self.total_pmt
+        )
+    }
+}
+
+impl Loan {
+    pub fn set_escrow(&mut self, escrow: Escrow) {
+        self.escrow = Some(escrow);
+        self.recalc_actual_pmts();
+    }
+
+    pub fn get_escrow(&self) -> Option<&Escrow> {
+        self.escrow.as_ref()
+    }
+
+    // escrow deposit with each payment: the annual disbursements spread over the payments in a year
+    pub fn get_escrow_pmt(&self) -> f64 {
+        match &self.escrow {
+            Some(escrow) => round(
+                escrow.get_annual_disbursements() / self.get_pmts_per_year(),
+                self.dec_places,
+            ),
+            None => 0.,
+        }
+    }
+
+    // the total initial payment: principal and interest plus escrow and any PMI premium
+    pub fn get_total_pmt_amount(&self) -> f64 {
+        round(
+            self.pmt_amount + self.get_escrow_pmt() + self.get_pmi_pmt(),
+            self.dec_places,
+        )
+    }
+
+    // the escrow cushion RESPA allows: two months of disbursements
+    pub fn get_escrow_cushion(&self) -> f64 {
+        match &self.escrow {
+            Some(escrow) => round(
+                escrow.get_annual_disbursements() * ESCROW_CUSHION_MONTHS / 12.,
+                self.dec_places,
+            ),
+            None => 0.,
+        }
+    }
+
+    // project a year of escrow activity from the first payment after start_date. The escrow
+    // payment is the annual disbursements spread over the year plus any shortage spread over
+    // the same payments. A surplus is reported but not netted against the payment.
+    pub fn get_escrow_analysis(
+        &self,
+        &start_date: &NaiveDate,
+        &begin_balance: &f64,
+    ) -> Option<EscrowAnalysis> {
+        let escrow = self.escrow.as_ref()?;
+        let deposit = self.get_escrow_pmt();
+
+        let mut pmt_date = self.first_pmt_date;
+        while pmt_date <= start_date {
+            pmt_date = get_next_pmt_date(&pmt_date, &self.pmt_schedule, &self.pmt_date_rule);
+        }
+
+        let mut analysis = EscrowAnalysis {
+            begin_balance,
+            low_balance: begin_balance,
+            required_cushion: self.get_escrow_cushion(),
+            ..Default::default()
+        };
+
+        let mut balance = begin_balance;
+        let mut begin_date = start_date;
+        let end_date = start_date
+            .checked_add_months(chrono::Months::new(12))
+            .unwrap();
+        while pmt_date <= end_date {
+            let disbursement = escrow.get_disbursements(&begin_date, &pmt_date);
+            balance += deposit - disbursement;
+            analysis.low_balance = analysis.low_balance.min(balance);
+            analysis.projections.push(EscrowProjection {
+                pmt_date,
+                deposit,
+                disbursement: round(disbursement, self.dec_places),
+                end_balance: round(balance, self.dec_places),
+            });
+            begin_date = pmt_date;
+            pmt_date = get_next_pmt_date(&pmt_date, &self.pmt_schedule, &self.pmt_date_rule);
+        }
+        trace!(
+            "escrow low balance {}, cushion {}",
+            analysis.low_balance,
+            analysis.required_cushion
+        );
+
+        let difference = analysis.low_balance - analysis.required_cushion;
+        analysis.low_balance = round(analysis.low_balance, self.dec_places);
+        analysis.shortage = round((-difference).max(0.), self.dec_places);
+        analysis.surplus = round(difference.max(0.), self.dec_places);
+        analysis.escrow_pmt = round(
+            deposit + analysis.shortage / analysis.projections.len().max(1) as f64,
+            self.dec_places,
+        );
+        analysis.total_pmt = round(self.pmt_amount + analysis.escrow_pmt, self.dec_places);
+        Some(analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Escrow;
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn new_loan() -> Loan {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.set_escrow(Escrow::new(
+            4800.,
+            1200.,
+            vec![
+                NaiveDate::from_ymd_opt(2024, 6, 15).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 15).unwrap(),
+            ],
+            vec![NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()],
+        ));
+        loan
+    }
+
+    #[test]
+    fn test_escrow_pmt() {
+        let loan = new_loan();
+        assert_eq!(loan.get_escrow_pmt(), 500.);
+        assert_eq!(loan.get_total_pmt_amount(), 2297.6565);
+        assert_eq!(loan.get_escrow_cushion(), 1000.);
+    }
+
+    #[test]
+    fn test_escrow_analysis() {
+        let loan = new_loan();
+        let analysis = loan
+            .get_escrow_analysis(&NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), &0.)
+            .unwrap();
+
+        assert_eq!(analysis.projections.len(), 12);
+        assert_eq!(analysis.projections[2].disbursement, 0.);
+        assert_eq!(analysis.projections[3].disbursement, 2400.);
+        assert_eq!(analysis.projections[3].end_balance, -400.);
+        assert_eq!(analysis.low_balance, -400.);
+        assert_eq!(analysis.shortage, 1400.);
+        assert_eq!(analysis.surplus, 0.);
+        assert_eq!(analysis.escrow_pmt, 616.6667);
+        assert_eq!(analysis.total_pmt, 2414.3232);
+
+        let analysis = loan
+            .get_escrow_analysis(&NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), &3000.)
+            .unwrap();
+        assert_eq!(analysis.low_balance, 2600.);
+        assert_eq!(analysis.shortage, 0.);
+        assert_eq!(analysis.surplus, 1600.);
+        assert_eq!(analysis.escrow_pmt, 500.);
+    }
+
+    #[test]
+    fn test_escrow_disbursement_dates() {
+        // no insurance dates, and a tax date on a leap day
+        let escrow = Escrow::new(
+            1200.,
+            600.,
+            vec![NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()],
+            vec![],
+        );
+        let disbursements = escrow.get_disbursements(
+            &NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            &NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+        );
+        assert_eq!(disbursements, 1200.);
+    }
+
+    #[test]
+    fn test_escrow_in_waterfall() {
+        let mut loan = new_loan();
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 2297.6565)
+            .unwrap();
+        assert_eq!(pmt.pmt_escrow_paid, 500.);
+        assert_eq!(pmt.pmt_interest_paid, 1166.6667);
+        assert_eq!(pmt.pmt_principal_paid, 630.9898);
+        assert_eq!(pmt.pmt_extra_principal, 0.);
+    }
+}
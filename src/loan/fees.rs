@@ -0,0 +1,192 @@
+use super::{round, Loan};
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FeeType {
+    #[default]
+    Origination,
+    DiscountPoints,
+    ClosingCost,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FeeAmount {
+    Flat(f64),   // fixed dollar amount
+    Points(f64), // percent of principal (i.e., 1.0 is one point)
+}
+
+impl Default for FeeAmount {
+    fn default() -> Self {
+        FeeAmount::Flat(0.)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoanFee {
+    pub fee_type: FeeType,
+    pub fee_amount: FeeAmount,
+    pub financed: bool, // true if the fee is added to the loan balance, false if paid in cash
+}
+
+impl LoanFee {
+    pub fn new(fee_type: FeeType, fee_amount: FeeAmount, financed: bool) -> Self {
+        Self {
+            fee_type,
+            fee_amount,
+            financed,
+        }
+    }
+
+    // dollar amount of the fee for a loan of the given principal
+    pub fn get_amount(&self, &principal: &f64) -> f64 {
+        match self.fee_amount {
+            FeeAmount::Flat(amt) => amt,
+            FeeAmount::Points(pct) => principal * pct / 100.,
+        }
+    }
+}
+
+impl fmt::Display for LoanFee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = match self.fee_amount {
+            FeeAmount::Flat(amt) => format!("${:.2}", amt),
+            FeeAmount::Points(pct) => format!("{} points", pct),
+        };
+        let paid = if self.financed {
+            "financed"
+        } else {
+            "paid in cash"
+        };
+        write!(f, "{:?} fee, {}, {}", self.fee_type, amount, paid)
+    }
+}
+
+impl Loan {
+    // add an upfront fee. Financed fees increase the loan balance and so change the payment schedule.
+    pub fn add_fee(&mut self, fee: LoanFee) {
+        self.fees.push(fee);
+        if fee.financed {
+            self.recalc_schedule();
+            self.recalc_actual_pmts();
+        }
+    }
+
+    pub fn get_fees(&self) -> &[LoanFee] {
+        &self.fees
+    }
+
+    pub fn get_total_fees(&self) -> f64 {
+        round(
+            self.fees
+                .iter()
+                .map(|fee| fee.get_amount(&self.principal))
+                .sum(),
+            self.dec_places,
+        )
+    }
+
+    // amount owed on the note: the principal plus any financed fees
+    pub fn get_note_amount(&self) -> f64 {
+        let financed: f64 = self
+            .fees
+            .iter()
+            .filter(|fee| fee.financed)
+            .map(|fee| fee.get_amount(&self.principal))
+            .sum();
+        round(self.principal + financed, self.dec_places)
+    }
+
+    // amount of credit actually provided to the borrower: the note amount less all upfront fees
+    pub fn get_amount_financed(&self) -> f64 {
+        round(
+            self.get_note_amount() - self.get_total_fees(),
+            self.dec_places,
+        )
+    }
+
+    // annual rate (same form as annual_rate, i.e. 7.25) at which the scheduled payments repay
+    // the amount financed. Fees raise the effective rate above the note rate.
+    pub fn get_effective_rate(&self) -> f64 {
+        let amount_financed = self.get_amount_financed();
+        let pmt_frequency = self.get_pmts_per_year();
+
+        // present value of the scheduled payments at a periodic rate
+        let present_value = |rate: f64| -> f64 {
+            self.scheduled_pmts
+                .iter()
+                .enumerate()
+                .map(|(i, pmt)| pmt.pmt_amount / (1. + rate).powi(i as i32 + 1))
+                .sum()
+        };
+
+        // present value falls as the rate rises, so bisect on the periodic rate
+        let mut low = 0.;
+        let mut high = 1.;
+        for _ in 0..200 {
+            let mid = (low + high) / 2.;
+            if present_value(mid) > amount_financed {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        trace!("effective periodic rate {}", low);
+
+        round((low + high) / 2. * pmt_frequency * 100., self.dec_places)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FeeAmount, FeeType, LoanFee};
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_cash_points() {
+        let base = new_test_loan(200000., 30., 7.);
+        let mut loan = new_test_loan(200000., 30., 7.);
+        loan.add_fee(LoanFee::new(
+            FeeType::DiscountPoints,
+            FeeAmount::Points(1.),
+            false,
+        ));
+
+        // fees paid in cash leave the payment schedule alone
+        assert_eq!(loan.get_pmt_amount(), base.get_pmt_amount());
+        assert_eq!(loan.get_pmt_info(&360), base.get_pmt_info(&360));
+        assert_eq!(loan.get_total_fees(), 2000.);
+        assert_eq!(loan.get_note_amount(), 200000.);
+        assert_eq!(loan.get_amount_financed(), 198000.);
+
+        assert_eq!(base.get_effective_rate(), 7.);
+        assert_eq!(loan.get_effective_rate(), 7.0999);
+    }
+
+    #[test]
+    fn test_financed_fees() {
+        let mut loan = new_test_loan(200000., 30., 7.);
+        loan.add_fee(LoanFee::new(
+            FeeType::Origination,
+            FeeAmount::Flat(3000.),
+            true,
+        ));
+        loan.add_fee(LoanFee::new(
+            FeeType::ClosingCost,
+            FeeAmount::Flat(1500.),
+            false,
+        ));
+
+        assert_eq!(loan.get_note_amount(), 203000.);
+        assert_eq!(loan.get_amount_financed(), 198500.);
+        assert_eq!(loan.get_pmt_amount(), &1350.5641);
+        assert_eq!(loan.get_pmt_count(), 360);
+    }
+}
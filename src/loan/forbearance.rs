@@ -0,0 +1,492 @@
+use super::{
+    add_scheduled_pmts, get_next_pmt_date, get_period_interest_rate, round, Compounding, Loan,
+    LoanPayment,
+};
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// what happens to the interest accruing while payments are suspended
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ForbearanceInterest {
+    #[default]
+    Capitalized, // added to the balance, which then bears interest
+    Deferred, // held apart from the balance without interest and repaid under the repayment plan
+    Waived,   // not charged, such as interest the government pays on a subsidized student loan
+}
+
+// how deferred interest is repaid once payments resume
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ForbearanceRepayment {
+    LumpSum,     // with the first payment after the forbearance
+    Spread(i32), // evenly over this many payments after the forbearance
+    #[default]
+    DeferToMaturity, // with the last payment
+}
+
+// scheduled payments suspended from start_pmt for pmt_count payments. Payments resume at the
+// regular amount afterwards, so the loan runs longer by the payments it missed.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Forbearance {
+    pub start_pmt: i32,
+    pub pmt_count: i32,
+    pub interest: ForbearanceInterest,
+    pub repayment: ForbearanceRepayment,
+}
+
+impl Forbearance {
+    pub fn new(
+        start_pmt: i32,
+        pmt_count: i32,
+        interest: ForbearanceInterest,
+        repayment: ForbearanceRepayment,
+    ) -> Self {
+        Self {
+            start_pmt,
+            pmt_count,
+            interest,
+            repayment,
+        }
+    }
+}
+
+impl fmt::Display for Forbearance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} payments from pmt number {}, interest {:?}, repayment {:?}",
+            self.pmt_count, self.start_pmt, self.interest, self.repayment
+        )
+    }
+}
+
+impl Loan {
+    // suspend scheduled payments and re-project the payments after the forbearance
+    pub fn add_forbearance(&mut self, forbearance: Forbearance) {
+        self.forbearances.push(forbearance);
+        self.forbearances
+            .sort_by_key(|forbearance| forbearance.start_pmt);
+        self.recalc_schedule();
+        self.recalc_actual_pmts();
+    }
+
+    // skip a single payment, capitalizing its interest
+    pub fn skip_pmt(&mut self, pmt_number: i32) {
+        self.add_forbearance(Forbearance::new(
+            pmt_number,
+            1,
+            ForbearanceInterest::Capitalized,
+            ForbearanceRepayment::DeferToMaturity,
+        ));
+    }
+
+    pub fn get_forbearances(&self) -> &[Forbearance] {
+        &self.forbearances
+    }
+
+    // interest deferred by all forbearances, repaid with the scheduled payments
+    pub fn get_deferred_interest(&self) -> f64 {
+        round(
+            self.deferred_interest
+                .iter()
+                .map(|(_, deferred, _)| deferred)
+                .sum(),
+            self.dec_places,
+        )
+    }
+
+    // regenerate the scheduled payments through and after a forbearance, continuing at the
+    // payment in effect when it starts. Returns the number of payments suspended.
+    pub(super) fn apply_forbearance(&mut self, forbearance: &Forbearance) -> usize {
+        let start = (forbearance.start_pmt.max(1) - 1) as usize;
+        let end = (start + forbearance.pmt_count.max(0) as usize).min(self.scheduled_pmts.len());
+        if start >= end {
+            return 0;
+        }
+
+        let (mut balance, mut begin_date) = match start {
+            0 => (self.get_note_amount(), self.loan_date),
+            _ => {
+                let pmt = &self.scheduled_pmts[start - 1];
+                (pmt.pmt_end_balance, pmt.pmt_date)
+            }
+        };
+        if balance <= 0. {
+            return 0;
+        }
+        let window_begin_date = begin_date;
+
+        // interest accrues without payments. Simple interest does not compound, so it is
+        // capitalized when the forbearance ends.
+        let mut deferred = 0.;
+        let mut accrued = 0.;
+        for (i, pmt) in self.scheduled_pmts[start..end].iter_mut().enumerate() {
+            let interest = balance
+                * get_period_interest_rate(
+                    &self.annual_rate,
+                    &self.pmt_schedule,
+                    &self.compound_type,
+                    &begin_date,
+                    &pmt.pmt_date,
+                );
+            match forbearance.interest {
+                ForbearanceInterest::Capitalized if self.compound_type == Compounding::Simple => {
+                    accrued += interest
+                }
+                ForbearanceInterest::Capitalized => balance += interest,
+                ForbearanceInterest::Deferred => deferred += interest,
+                ForbearanceInterest::Waived => {}
+            }
+            if start + i + 1 == end {
+                balance += accrued;
+            }
+            *pmt = LoanPayment {
+                pmt_nominal_date: pmt.pmt_nominal_date,
+                ..LoanPayment::new(
+                    pmt.pmt_number,
+                    pmt.pmt_date,
+                    0.,
+                    0.,
+                    round(balance, self.dec_places),
+                )
+            };
+            pmt.pmt_principal_paid = 0.;
+            begin_date = pmt.pmt_date;
+        }
+        trace!(
+            "forbearance {}, balance {}, deferred interest {}",
+            forbearance,
+            balance,
+            deferred
+        );
+
+        // one-time prepayments due during the forbearance are paid with the first payment after it
+        let last_pmt = self.scheduled_pmts[end - 1];
+        let mut prepayment_plan = self.prepayment_plan.after_pmt(&last_pmt.pmt_number);
+        for (pmt_date, amount) in prepayment_plan.one_time.iter_mut() {
+            if *pmt_date > window_begin_date && *pmt_date <= last_pmt.pmt_date {
+                trace!(
+                    "prepayment of {} on {} moved after forbearance",
+                    amount,
+                    pmt_date
+                );
+                *pmt_date = last_pmt.pmt_date.succ_opt().unwrap();
+            }
+        }
+
+        // re-project the payments after the forbearance at the regular payment
+        let remaining_pmts = add_scheduled_pmts(
+            &round(balance, self.dec_places),
+            &last_pmt.pmt_date,
+            &get_next_pmt_date(
+                &last_pmt.pmt_nominal_date,
+                &self.pmt_schedule,
+                &self.pmt_date_rule,
+            ),
+            &self.annual_rate,
+            &self.pmt_schedule,
+            &self.pmt_date_rule,
+            &self.compound_type,
+            &self.dec_places,
+            self.pmt_amount,
+            &prepayment_plan,
+        );
+        self.scheduled_pmts.truncate(end);
+        self.scheduled_pmts
+            .extend(remaining_pmts.into_iter().map(|mut pmt| {
+                pmt.pmt_number += last_pmt.pmt_number;
+                pmt
+            }));
+
+        if deferred > 0. {
+            self.deferred_interest.push((
+                end,
+                round(deferred, self.dec_places),
+                forbearance.repayment,
+            ));
+        }
+        end - start
+    }
+
+    // add deferred interest to the payments from index first under the repayment plan,
+    // skipping payments suspended by a later forbearance
+    pub(super) fn repay_deferred(
+        &mut self,
+        &first: &usize,
+        &deferred: &f64,
+        &repayment: &ForbearanceRepayment,
+    ) {
+        let last = self.scheduled_pmts.len();
+        if deferred <= 0. || first >= last {
+            return;
+        }
+
+        let due: Vec<usize> = (first..last)
+            .filter(|i| self.scheduled_pmts[*i].pmt_amount > 0.)
+            .collect();
+        let pmts: Vec<usize> = match repayment {
+            ForbearanceRepayment::LumpSum => due.into_iter().take(1).collect(),
+            ForbearanceRepayment::Spread(count) => {
+                due.into_iter().take(count.max(1) as usize).collect()
+            }
+            ForbearanceRepayment::DeferToMaturity => vec![last - 1],
+        };
+        let count = pmts.len();
+        if count == 0 {
+            return;
+        }
+        let installment = round(deferred / count as f64, self.dec_places);
+        for (i, pmt) in pmts.into_iter().enumerate() {
+            let pmt = &mut self.scheduled_pmts[pmt];
+            // the last installment takes any rounding difference
+            let amount = if i == count - 1 {
+                round(deferred - installment * (count - 1) as f64, self.dec_places)
+            } else {
+                installment
+            };
+            pmt.pmt_amount = round(pmt.pmt_amount + amount, self.dec_places);
+            pmt.pmt_interest_paid = round(pmt.pmt_interest_paid + amount, self.dec_places);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Forbearance, ForbearanceInterest, ForbearanceRepayment};
+    use crate::loan::PrepaymentPlan;
+    use crate::loan::{new_test_loan, round, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_capitalized_forbearance() {
+        let base = new_test_loan(200000., 15., 7.);
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_forbearance(Forbearance::new(
+            13,
+            6,
+            ForbearanceInterest::Capitalized,
+            ForbearanceRepayment::default(),
+        ));
+
+        let base_pmts = base.get_scheduled_pmts();
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[11], base_pmts[11]);
+        assert_eq!(pmts[12].pmt_amount, 0.);
+        assert_eq!(
+            pmts[12].pmt_end_balance,
+            (base_pmts[11].pmt_end_balance * (1. + 0.07 / 12.) * 10000.).round() / 10000.
+        );
+        assert!(pmts[17].pmt_end_balance > pmts[12].pmt_end_balance);
+
+        // payments resume at the regular amount and run past the original maturity
+        assert_eq!(pmts[18].pmt_amount, 1797.6565);
+        assert_eq!(pmts[18].pmt_number, 19);
+        assert!(pmts.len() > 186);
+        assert_eq!(pmts.last().unwrap().pmt_end_balance, 0.);
+    }
+
+    #[test]
+    fn test_deferred_forbearance() {
+        let deferred = |repayment| {
+            let mut loan = new_test_loan(200000., 15., 7.);
+            loan.add_forbearance(Forbearance::new(
+                13,
+                6,
+                ForbearanceInterest::Deferred,
+                repayment,
+            ));
+            loan
+        };
+
+        // the balance is unchanged through the forbearance and the term runs six payments longer
+        let loan = deferred(ForbearanceRepayment::LumpSum);
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[17].pmt_end_balance, pmts[11].pmt_end_balance);
+        assert_eq!(pmts.len(), 186);
+        let deferred_interest =
+            (pmts[11].pmt_end_balance * 0.07 / 12. * 6. * 10000.).round() / 10000.;
+        assert!((pmts[18].pmt_amount - 1797.6565 - deferred_interest).abs() < 0.001);
+
+        let loan = deferred(ForbearanceRepayment::Spread(12));
+        let pmts = loan.get_scheduled_pmts();
+        let repaid: f64 = pmts[18..30]
+            .iter()
+            .map(|pmt| pmt.pmt_amount - 1797.6565)
+            .sum();
+        assert!((repaid - deferred_interest).abs() < 0.001);
+        assert_eq!(pmts[30].pmt_amount, 1797.6565);
+
+        let loan = deferred(ForbearanceRepayment::DeferToMaturity);
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[18].pmt_amount, 1797.6565);
+        assert!(pmts[185].pmt_amount > deferred_interest);
+    }
+
+    #[test]
+    fn test_deferred_forbearance_before_recast() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_forbearance(Forbearance::new(
+            13,
+            6,
+            ForbearanceInterest::Deferred,
+            ForbearanceRepayment::DeferToMaturity,
+        ));
+        let deferred_interest = loan.get_deferred_interest();
+        assert!(deferred_interest > 6700.);
+
+        // the recast rebuilds the payments after it without losing the deferred interest
+        loan.recast(NaiveDate::from_ymd_opt(2026, 1, 15).unwrap());
+        assert_eq!(loan.get_deferred_interest(), deferred_interest);
+        let pmts = loan.get_scheduled_pmts();
+        let last_pmt = pmts.last().unwrap();
+        assert_eq!(last_pmt.pmt_end_balance, 0.);
+        assert!(
+            (last_pmt.pmt_amount - pmts[pmts.len() - 2].pmt_amount - deferred_interest).abs() < 1.
+        );
+    }
+
+    #[test]
+    fn test_two_forbearances() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_forbearance(Forbearance::new(
+            13,
+            6,
+            ForbearanceInterest::Deferred,
+            ForbearanceRepayment::DeferToMaturity,
+        ));
+        let first = loan.get_scheduled_pmts().to_vec();
+        let deferred_interest = loan.get_deferred_interest();
+
+        // the second forbearance rebuilds the payments after it, including the last payment
+        loan.add_forbearance(Forbearance::new(
+            40,
+            3,
+            ForbearanceInterest::Capitalized,
+            ForbearanceRepayment::default(),
+        ));
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[38], first[38]);
+        assert_eq!(pmts[39].pmt_amount, 0.);
+        assert!(pmts.len() >= first.len() + 3);
+        let last_pmt = pmts.last().unwrap();
+        assert!(last_pmt.pmt_amount > deferred_interest);
+        assert_eq!(loan.get_deferred_interest(), deferred_interest);
+
+        // a lump sum is repaid with the first payment that is not suspended
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_forbearance(Forbearance::new(
+            13,
+            6,
+            ForbearanceInterest::Deferred,
+            ForbearanceRepayment::LumpSum,
+        ));
+        loan.add_forbearance(Forbearance::new(
+            19,
+            2,
+            ForbearanceInterest::Capitalized,
+            ForbearanceRepayment::default(),
+        ));
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[18].pmt_amount, 0.);
+        assert_eq!(pmts[19].pmt_amount, 0.);
+        assert!((pmts[20].pmt_amount - 1797.6565 - loan.get_deferred_interest()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_skip_pmt() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.skip_pmt(1);
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[0].pmt_amount, 0.);
+        assert_eq!(pmts[0].pmt_end_balance, 201166.6667);
+        assert_eq!(pmts[1].pmt_amount, 1797.6565);
+        assert_eq!(loan.get_forbearances().len(), 1);
+    }
+
+    #[test]
+    fn test_forbearance_before_recast() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        let mut plan = PrepaymentPlan::default();
+        plan.add_one_time(NaiveDate::from_ymd_opt(2025, 5, 10).unwrap(), 20000.);
+        loan.set_prepayment_plan(plan);
+        loan.add_forbearance(Forbearance::new(
+            13,
+            3,
+            ForbearanceInterest::Capitalized,
+            ForbearanceRepayment::default(),
+        ));
+        loan.recast(NaiveDate::from_ymd_opt(2025, 12, 1).unwrap());
+
+        // the prepayment due during the forbearance is paid with the first payment after it
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(
+            pmts[15].pmt_date,
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()
+        );
+        assert_eq!(pmts[15].pmt_extra_principal, 20000.);
+
+        // payments resume at the original payment until the recast
+        assert_eq!(pmts[15].pmt_amount, 21797.6565);
+        assert_eq!(pmts[20].pmt_amount, 1797.6565);
+        assert!(pmts[21].pmt_amount < 1700.);
+
+        // the recast amortizes over the original term extended by the suspended payments
+        assert_eq!(pmts.len(), 183);
+        assert_eq!(pmts[182].pmt_end_balance, 0.);
+        assert!((pmts[182].pmt_amount - pmts[21].pmt_amount).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_simple_interest_forbearance() {
+        let new_loan = || {
+            Loan::new(
+                100000.,
+                10.,
+                6.,
+                PmtSchedule::Monthly,
+                Compounding::Simple,
+                NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+                2.,
+            )
+        };
+
+        // the level payment is solved by the days in each period, so the last payment is close
+        let mut loan = new_loan();
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts.len(), 120);
+        assert!((pmts[119].pmt_amount - loan.get_pmt_amount()).abs() < 0.25);
+
+        // simple interest is capitalized when the forbearance ends, without compounding
+        loan.add_forbearance(Forbearance::new(
+            5,
+            3,
+            ForbearanceInterest::Capitalized,
+            ForbearanceRepayment::default(),
+        ));
+        let pmts = loan.get_scheduled_pmts();
+        let balance = pmts[3].pmt_end_balance;
+        assert_eq!(pmts[5].pmt_end_balance, balance);
+        assert_eq!(
+            pmts[6].pmt_end_balance,
+            round(balance * (1. + 0.06 * 92. / 365.), 2.)
+        );
+
+        // waived interest leaves the balance unchanged
+        let mut waived = new_loan();
+        waived.add_forbearance(Forbearance::new(
+            5,
+            3,
+            ForbearanceInterest::Waived,
+            ForbearanceRepayment::default(),
+        ));
+        let pmts = waived.get_scheduled_pmts();
+        assert_eq!(pmts[6].pmt_end_balance, pmts[3].pmt_end_balance);
+        assert_eq!(waived.get_deferred_interest(), 0.);
+    }
+}
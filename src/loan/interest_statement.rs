@@ -0,0 +1,210 @@
+use super::{round, FeeAmount, FeeType, Loan};
+use chrono::{Datelike, NaiveDate};
+use log::warn;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// annual mortgage interest statement, laid out like Form 1098
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InterestStatement {
+    pub tax_year: i32,
+    pub interest_received: f64,      // box 1
+    pub outstanding_principal: f64,  // box 2: principal on January 1, or at origination if later
+    pub origination_date: NaiveDate, // box 3
+    pub points_paid: f64,            // box 6: points paid in the year the loan originated
+    pub pmt_count: usize,
+}
+
+impl InterestStatement {
+    pub fn get_csv_header() -> &'static str {
+        "tax_year,interest_received,outstanding_principal,origination_date,points_paid,pmt_count"
+    }
+
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{:.2},{:.2},{},{:.2},{}",
+            self.tax_year,
+            self.interest_received,
+            self.outstanding_principal,
+            self.origination_date,
+            self.points_paid,
+            self.pmt_count
+        )
+    }
+}
+
+impl fmt::Display for InterestStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Mortgage interest statement for {}", self.tax_year)?;
+        writeln!(
+            f,
+            "  1 Mortgage interest received        ${:.2}",
+            self.interest_received
+        )?;
+        writeln!(
+            f,
+            "  2 Outstanding mortgage principal    ${:.2}",
+            self.outstanding_principal
+        )?;
+        writeln!(
+            f,
+            "  3 Mortgage origination date         {}",
+            self.origination_date
+        )?;
+        writeln!(
+            f,
+            "  6 Points paid on purchase           ${:.2}",
+            self.points_paid
+        )?;
+        write!(
+            f,
+            "    Payments received                 {}",
+            self.pmt_count
+        )
+    }
+}
+
+impl Loan {
+    // interest statement for a tax year, built from the actual payments received in the year.
+    // Returns None for a year chrono cannot represent.
+    pub fn get_interest_statement(&self, &tax_year: &i32) -> Option<InterestStatement> {
+        let year_start = match NaiveDate::from_ymd_opt(tax_year, 1, 1) {
+            Some(year_start) => year_start,
+            None => {
+                warn!("tax year {} is not valid", tax_year);
+                return None;
+            }
+        };
+        let pmts = self
+            .actual_pmts
+            .iter()
+            .filter(|pmt| pmt.pmt_date.year() == tax_year);
+
+        let outstanding_principal = match self
+            .actual_pmts
+            .iter()
+            .rev()
+            .find(|pmt| pmt.pmt_date < year_start)
+        {
+            Some(pmt) => pmt.pmt_end_balance,
+            None => self.get_note_amount(),
+        };
+
+        let points_paid = if self.loan_date.year() == tax_year {
+            self.fees
+                .iter()
+                .filter(|fee| {
+                    fee.fee_type == FeeType::DiscountPoints
+                        || matches!(fee.fee_amount, FeeAmount::Points(_))
+                })
+                .map(|fee| fee.get_amount(&self.principal))
+                .sum()
+        } else {
+            0.
+        };
+
+        Some(InterestStatement {
+            tax_year,
+            interest_received: round(
+                pmts.clone().map(|pmt| pmt.pmt_interest_paid).sum(),
+                self.dec_places,
+            ),
+            outstanding_principal,
+            origination_date: self.loan_date,
+            points_paid: round(points_paid, self.dec_places),
+            pmt_count: pmts.count(),
+        })
+    }
+
+    // statements for every year with actual payments, as CSV with a header row
+    pub fn get_interest_statements_csv(&self) -> String {
+        let mut years: Vec<i32> = self
+            .actual_pmts
+            .iter()
+            .map(|pmt| pmt.pmt_date.year())
+            .collect();
+        years.dedup();
+
+        let mut csv = InterestStatement::get_csv_header().to_string();
+        for statement in years
+            .iter()
+            .filter_map(|year| self.get_interest_statement(year))
+        {
+            csv.push('\n');
+            csv.push_str(&statement.to_csv());
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loan::{Compounding, FeeAmount, FeeType, Loan, LoanFee, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn new_loan() -> Loan {
+        let mut loan = Loan::new(
+            200000.,
+            15.,
+            7.,
+            PmtSchedule::Monthly,
+            Compounding::Monthly,
+            NaiveDate::from_ymd_opt(2024, 10, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 12, 1).unwrap(),
+            2.,
+        );
+        loan.add_fee(LoanFee::new(
+            FeeType::DiscountPoints,
+            FeeAmount::Points(1.5),
+            false,
+        ));
+        loan.add_fee(LoanFee::new(
+            FeeType::ClosingCost,
+            FeeAmount::Flat(900.),
+            false,
+        ));
+        for (i, (yr, mon)) in [(2024, 12), (2025, 1), (2025, 2)].iter().enumerate() {
+            loan.add_actual_pmt(
+                i as i32 + 1,
+                NaiveDate::from_ymd_opt(*yr, *mon, 1).unwrap(),
+                1797.66,
+            );
+        }
+        loan
+    }
+
+    #[test]
+    fn test_interest_statement() {
+        let loan = new_loan();
+
+        let statement = loan.get_interest_statement(&2024).unwrap();
+        assert_eq!(statement.pmt_count, 1);
+        assert_eq!(statement.interest_received, 1166.67);
+        assert_eq!(statement.outstanding_principal, 200000.);
+        assert_eq!(statement.points_paid, 3000.);
+
+        let statement = loan.get_interest_statement(&2025).unwrap();
+        assert_eq!(statement.pmt_count, 2);
+        assert_eq!(statement.outstanding_principal, 199369.01);
+        assert_eq!(statement.interest_received, 2322.27);
+        assert_eq!(statement.points_paid, 0.);
+        assert_eq!(
+            statement.to_csv(),
+            "2025,2322.27,199369.01,2024-10-15,0.00,2"
+        );
+
+        assert_eq!(loan.get_interest_statement(&i32::MAX), None);
+    }
+
+    #[test]
+    fn test_interest_statements_csv() {
+        let loan = new_loan();
+        let csv = loan.get_interest_statements_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("2024,1166.67,200000.00,2024-10-15,3000.00,1"));
+    }
+}
@@ -0,0 +1,206 @@
+use super::{get_next_pmt_date, round, Loan, LoanPayment};
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// borrowers may request cancellation at 80% LTV; PMI terminates automatically at 78% LTV
+const PMI_REQUEST_LTV: f64 = 80.;
+const PMI_TERMINATION_LTV: f64 = 78.;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pmi {
+    pub annual_rate: f64, // annual premium as a percent of the note amount (i.e., 0.5)
+    pub property_value: f64, // original value of the property
+}
+
+impl Pmi {
+    pub fn new(annual_rate: f64, property_value: f64) -> Self {
+        Self {
+            annual_rate,
+            property_value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PmiSummary {
+    pub pmi_pmt: f64,
+    pub original_ltv: f64,
+    pub request_date: Option<NaiveDate>, // date the scheduled balance reaches 80% LTV
+    pub termination_date: Option<NaiveDate>, // date PMI drops off
+    pub pmi_pmt_count: usize,
+    pub total_cost: f64,
+}
+
+impl fmt::Display for PmiSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |date: Option<NaiveDate>| match date {
+            Some(date) => date.to_string(),
+            None => "none".to_string(),
+        };
+        write!(
+            f,
+            "pmi payment ${:.2}, original ltv {:.2}%, cancel by request {}, terminates {}, {} payments, total cost ${:.2}",
+            self.pmi_pmt,
+            self.original_ltv,
+            show(self.request_date),
+            show(self.termination_date),
+            self.pmi_pmt_count,
+            self.total_cost
+        )
+    }
+}
+
+impl Loan {
+    pub fn set_pmi(&mut self, pmi: Pmi) {
+        self.pmi = Some(pmi);
+        self.recalc_actual_pmts();
+    }
+
+    pub fn get_pmi(&self) -> Option<&Pmi> {
+        self.pmi.as_ref()
+    }
+
+    // PMI premium with each payment while PMI is in force
+    pub fn get_pmi_pmt(&self) -> f64 {
+        match &self.pmi {
+            Some(pmi) => round(
+                self.get_note_amount() * pmi.annual_rate / 100. / self.get_pmts_per_year(),
+                self.dec_places,
+            ),
+            None => 0.,
+        }
+    }
+
+    // loan-to-value of a balance against the original property value, as a percent
+    pub fn get_ltv(&self, &balance: &f64) -> Option<f64> {
+        let pmi = self.pmi.as_ref()?;
+        Some(round(balance / pmi.property_value * 100., self.dec_places))
+    }
+
+    // first scheduled payment that brings the balance to ltv percent of the original value
+    fn get_pmt_at_ltv(&self, &ltv: &f64) -> Option<&LoanPayment> {
+        let pmi = self.pmi.as_ref()?;
+        let target = pmi.property_value * ltv / 100.;
+        self.scheduled_pmts
+            .iter()
+            .find(|pmt| pmt.pmt_end_balance <= target)
+    }
+
+    // date the borrower may request cancellation of PMI
+    pub fn get_pmi_request_date(&self) -> Option<NaiveDate> {
+        self.get_pmt_at_ltv(&PMI_REQUEST_LTV)
+            .map(|pmt| pmt.pmt_date)
+    }
+
+    // date PMI terminates: the scheduled balance reaches 78% LTV, or the midpoint of the
+    // original amortization term, whichever comes first
+    pub fn get_pmi_termination_date(&self) -> Option<NaiveDate> {
+        self.pmi.as_ref()?;
+        let midpoint = self.get_pmi_midpoint_date();
+        match self.get_pmt_at_ltv(&PMI_TERMINATION_LTV) {
+            Some(pmt) if pmt.pmt_date < midpoint => Some(pmt.pmt_date),
+            _ => Some(midpoint),
+        }
+    }
+
+    // due date of the payment that completes half of the original term. Extra payments and
+    // recasts do not move it.
+    fn get_pmi_midpoint_date(&self) -> NaiveDate {
+        let midpoint_pmt = (self.term * self.get_pmts_per_year() / 2.).round() as usize;
+        let nominal_date = (1..midpoint_pmt.max(1)).fold(self.first_pmt_date, |date, _| {
+            get_next_pmt_date(&date, &self.pmt_schedule, &self.pmt_date_rule)
+        });
+        self.pmt_date_rule.adjust(&nominal_date)
+    }
+
+    pub fn get_pmi_summary(&self) -> Option<PmiSummary> {
+        let pmi = self.pmi.as_ref()?;
+        let termination_date = self.get_pmi_termination_date();
+        let pmi_pmt = self.get_pmi_pmt();
+
+        // premiums are paid with each payment up to the termination date
+        let pmi_pmt_count = self
+            .scheduled_pmts
+            .iter()
+            .take_while(|pmt| Some(pmt.pmt_date) < termination_date)
+            .count();
+
+        Some(PmiSummary {
+            pmi_pmt,
+            original_ltv: round(
+                self.get_note_amount() / pmi.property_value * 100.,
+                self.dec_places,
+            ),
+            request_date: self.get_pmi_request_date(),
+            termination_date,
+            pmi_pmt_count,
+            total_cost: round(pmi_pmt * pmi_pmt_count as f64, self.dec_places),
+        })
+    }
+
+    // true while PMI is charged with the payment due on pmt_date
+    pub fn is_pmi_active(&self, &pmt_date: &NaiveDate) -> bool {
+        match self.get_pmi_termination_date() {
+            Some(termination_date) => pmt_date < termination_date,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pmi;
+    use crate::loan::{Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_pmi() {
+        let mut loan = Loan::new(
+            200000.,
+            30.,
+            7.,
+            PmtSchedule::Monthly,
+            Compounding::Monthly,
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+            2.,
+        );
+        assert_eq!(loan.get_pmi_summary(), None);
+
+        loan.set_pmi(Pmi::new(0.6, 225000.));
+        assert_eq!(loan.get_pmi_pmt(), 100.);
+        assert_eq!(loan.get_ltv(&200000.), Some(88.89));
+        assert_eq!(loan.get_total_pmt_amount(), 1430.6);
+
+        let summary = loan.get_pmi_summary().unwrap();
+        assert_eq!(summary.request_date, NaiveDate::from_ymd_opt(2031, 12, 1));
+        assert_eq!(
+            summary.termination_date,
+            NaiveDate::from_ymd_opt(2033, 3, 1)
+        );
+        assert_eq!(summary.pmi_pmt_count, 107);
+        assert_eq!(summary.total_cost, 10700.);
+
+        assert!(loan.is_pmi_active(&NaiveDate::from_ymd_opt(2033, 2, 1).unwrap()));
+        assert!(!loan.is_pmi_active(&NaiveDate::from_ymd_opt(2033, 3, 1).unwrap()));
+
+        // the premium has its own bucket in the payment waterfall
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1430.6)
+            .unwrap();
+        assert_eq!(pmt.pmt_pmi_paid, 100.);
+        assert_eq!(pmt.pmt_extra_principal, 0.);
+
+        // a balance still above 78% LTV at the midpoint of the term terminates at the midpoint
+        loan.set_pmi(Pmi::new(0.6, 185000.));
+        assert_eq!(
+            loan.get_pmi_termination_date(),
+            NaiveDate::from_ymd_opt(2039, 3, 1)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use super::{round, Loan, LoanPayment};
+use chrono::{Datelike, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// extra principal payments made on top of the scheduled payment
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PrepaymentPlan {
+    pub one_time: Vec<(NaiveDate, f64)>, // extra amount paid with the payment on or after the date
+    pub recurring_start: i32,            // first payment number with the recurring extra amount
+    pub recurring_amount: f64,
+    pub annual_month: u32, // month of the year (1 - 12) the annual lump sum is paid
+    pub annual_amount: f64,
+}
+
+impl PrepaymentPlan {
+    pub fn add_one_time(&mut self, pmt_date: NaiveDate, amount: f64) {
+        self.one_time.push((pmt_date, amount));
+    }
+
+    pub fn set_recurring(&mut self, recurring_start: i32, recurring_amount: f64) {
+        self.recurring_start = recurring_start;
+        self.recurring_amount = recurring_amount;
+    }
+
+    pub fn set_annual(&mut self, annual_month: u32, annual_amount: f64) {
+        self.annual_month = annual_month;
+        self.annual_amount = annual_amount;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.one_time.is_empty() && self.recurring_amount == 0. && self.annual_amount == 0.
+    }
+
+    // the plan for a schedule that continues after pmt_number and is numbered from 1 again
+    pub(super) fn after_pmt(&self, &pmt_number: &i32) -> Self {
+        let mut plan = self.clone();
+        if plan.recurring_start > 0 {
+            plan.recurring_start = (plan.recurring_start - pmt_number).max(1);
+        }
+        plan
+    }
+
+    // extra principal paid with payment pmt_number, which covers the period after begin_date
+    // up to and including end_date
+    pub fn get_extra_pmt(
+        &self,
+        &pmt_number: &i32,
+        &begin_date: &NaiveDate,
+        &end_date: &NaiveDate,
+    ) -> f64 {
+        let mut extra: f64 = self
+            .one_time
+            .iter()
+            .filter(|(pmt_date, _)| *pmt_date > begin_date && *pmt_date <= end_date)
+            .map(|(_, amount)| amount)
+            .sum();
+
+        if self.recurring_start > 0 && pmt_number >= self.recurring_start {
+            extra += self.recurring_amount;
+        }
+
+        // the annual lump sum goes with the first payment in the month
+        if end_date.month() == self.annual_month
+            && (begin_date.month() != self.annual_month || begin_date.year() != end_date.year())
+        {
+            extra += self.annual_amount;
+        }
+        extra
+    }
+}
+
+// the effect of a prepayment plan compared to the same loan without it
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PrepaymentSavings {
+    pub baseline_interest: f64,
+    pub interest: f64,
+    pub interest_saved: f64,
+    pub baseline_pmt_count: usize,
+    pub pmt_count: usize,
+    pub baseline_payoff_date: NaiveDate,
+    pub payoff_date: NaiveDate,
+    pub months_saved: i32,
+}
+
+impl fmt::Display for PrepaymentSavings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "interest ${:.2} vs ${:.2}, saved ${:.2}; payoff {} vs {} ({} vs {} payments), {} months sooner",
+            self.interest,
+            self.baseline_interest,
+            self.interest_saved,
+            self.payoff_date,
+            self.baseline_payoff_date,
+            self.pmt_count,
+            self.baseline_pmt_count,
+            self.months_saved
+        )
+    }
+}
+
+fn get_total_interest(pmts: &[LoanPayment]) -> f64 {
+    pmts.iter().map(|pmt| pmt.pmt_interest_paid).sum()
+}
+
+fn get_payoff_date(pmts: &[LoanPayment]) -> NaiveDate {
+    pmts.last().map(|pmt| pmt.pmt_date).unwrap_or_default()
+}
+
+impl Loan {
+    pub fn set_prepayment_plan(&mut self, prepayment_plan: PrepaymentPlan) {
+        self.prepayment_plan = prepayment_plan;
+        self.recalc_schedule();
+        self.recalc_actual_pmts();
+    }
+
+    pub fn get_prepayment_plan(&self) -> &PrepaymentPlan {
+        &self.prepayment_plan
+    }
+
+    // compare the scheduled payments with the prepayment plan to the loan without it
+    pub fn get_prepayment_savings(&self) -> PrepaymentSavings {
+        let mut baseline = self.clone();
+        baseline.prepayment_plan = PrepaymentPlan::default();
+        baseline.recalc_schedule();
+
+        let baseline_interest = get_total_interest(&baseline.scheduled_pmts);
+        let interest = get_total_interest(&self.scheduled_pmts);
+        let baseline_payoff_date = get_payoff_date(&baseline.scheduled_pmts);
+        let payoff_date = get_payoff_date(&self.scheduled_pmts);
+
+        PrepaymentSavings {
+            baseline_interest: round(baseline_interest, self.dec_places),
+            interest: round(interest, self.dec_places),
+            interest_saved: round(baseline_interest - interest, self.dec_places),
+            baseline_pmt_count: baseline.scheduled_pmts.len(),
+            pmt_count: self.scheduled_pmts.len(),
+            baseline_payoff_date,
+            payoff_date,
+            months_saved: (baseline_payoff_date.year() - payoff_date.year()) * 12
+                + baseline_payoff_date.month() as i32
+                - payoff_date.month() as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrepaymentPlan;
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_recurring_prepayment() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        let mut plan = PrepaymentPlan::default();
+        plan.set_recurring(1, 200.);
+        loan.set_prepayment_plan(plan);
+
+        assert_eq!(loan.get_pmt_amount(), &1797.6565);
+        assert_eq!(loan.get_pmt_info(&1), "pmt number 1, date 2024-04-01, payment $1997.6565, interest paid $1166.6667, ending balance $199169.0102");
+
+        let savings = loan.get_prepayment_savings();
+        assert_eq!(savings.baseline_pmt_count, 180);
+        assert_eq!(savings.pmt_count, 151);
+        assert_eq!(savings.months_saved, 29);
+        assert_eq!(savings.baseline_interest, 123578.1826);
+        assert_eq!(savings.interest_saved, 22330.1196);
+    }
+
+    #[test]
+    fn test_one_time_and_annual_prepayment() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        let mut plan = PrepaymentPlan::default();
+        plan.add_one_time(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), 10000.);
+        plan.set_annual(12, 1000.);
+        loan.set_prepayment_plan(plan);
+
+        let pmts = loan.get_scheduled_pmts();
+        let pmt = pmts[2];
+        assert_eq!(pmt.pmt_date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        assert_eq!(pmt.pmt_extra_principal, 10000.);
+        let pmt = pmts[8];
+        assert_eq!(pmt.pmt_date, NaiveDate::from_ymd_opt(2024, 12, 1).unwrap());
+        assert_eq!(pmt.pmt_extra_principal, 1000.);
+        assert_eq!(pmts[9].pmt_extra_principal, 0.);
+
+        let savings = loan.get_prepayment_savings();
+        assert!(savings.interest_saved > 0.);
+        assert_eq!(savings.months_saved as usize, 180 - loan.get_pmt_count());
+    }
+}
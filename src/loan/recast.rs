@@ -0,0 +1,233 @@
+use super::{
+    add_scheduled_pmts, get_level_pmt_amount, get_next_pmt_date, get_pmt_amount, round, Loan,
+    LoanPayment, PmtSchedule,
+};
+use chrono::NaiveDate;
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecastScenario {
+    pub pmt_amount: f64, // regular payment after the recast date
+    pub pmt_count: usize,
+    pub payoff_date: NaiveDate,
+    pub total_interest: f64,
+}
+
+impl fmt::Display for RecastScenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "payment ${:.2}, {} payments, payoff {}, total interest ${:.2}",
+            self.pmt_amount, self.pmt_count, self.payoff_date, self.total_interest
+        )
+    }
+}
+
+// a recast compared to keeping the payment and shortening the term
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecastComparison {
+    pub recast_date: NaiveDate,
+    pub keep_pmt: RecastScenario,
+    pub recast: RecastScenario,
+}
+
+impl fmt::Display for RecastComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "recast on {}", self.recast_date)?;
+        writeln!(f, "  keep payment: {}", self.keep_pmt)?;
+        write!(f, "  recast:       {}", self.recast)
+    }
+}
+
+impl Loan {
+    // re-amortize the balance after the last scheduled payment on or before recast_date over the
+    // remaining periods of the original term. Payments up to that point are kept. Returns the new
+    // payment amount, or None if there is nothing left to recast.
+    pub fn recast(&mut self, recast_date: NaiveDate) -> Option<f64> {
+        let mut loan = self.clone();
+        loan.recast_dates.push(recast_date);
+        loan.recast_dates.sort();
+        loan.recalc_schedule();
+        if loan.scheduled_pmts == self.scheduled_pmts {
+            return None;
+        }
+
+        *self = loan;
+        self.recalc_actual_pmts();
+        Some(self.pmt_amount)
+    }
+
+    pub fn get_recast_dates(&self) -> &[NaiveDate] {
+        &self.recast_dates
+    }
+
+    // regenerate the scheduled payments from recast_date over the rest of the original term,
+    // extended by forborne_pmts payments suspended by earlier forbearances
+    pub(super) fn apply_recast(&mut self, &recast_date: &NaiveDate, &forborne_pmts: &usize) {
+        let level_pmt_dates = self.get_level_pmt_dates(&forborne_pmts);
+        let total_pmts = match &level_pmt_dates {
+            Some(pmt_dates) => pmt_dates.len(),
+            None => (self.term * self.get_pmts_per_year()).round() as usize + forborne_pmts,
+        };
+
+        let pmts_made = self
+            .scheduled_pmts
+            .iter()
+            .take_while(|pmt| pmt.pmt_date <= recast_date)
+            .count();
+        let last_pmt = match pmts_made {
+            0 => return,
+            _ => self.scheduled_pmts[pmts_made - 1],
+        };
+        if last_pmt.pmt_end_balance <= 0. || pmts_made >= total_pmts {
+            return;
+        }
+
+        let remaining_term = (total_pmts - pmts_made) as f64 / self.get_pmts_per_year();
+        self.pmt_amount = match &level_pmt_dates {
+            Some(pmt_dates) => get_level_pmt_amount(
+                &last_pmt.pmt_end_balance,
+                &last_pmt.pmt_date,
+                &pmt_dates[pmts_made..],
+                &self.annual_rate,
+                &self.compound_type,
+                &self.dec_places,
+            ),
+            None => get_pmt_amount(
+                &last_pmt.pmt_end_balance,
+                &remaining_term,
+                &self.annual_rate,
+                &self.pmt_schedule,
+                &self.compound_type,
+                &self.dec_places,
+            ),
+        };
+        trace!(
+            "recast on {} after pmt # {}, balance {}, new pmt {}",
+            recast_date,
+            last_pmt.pmt_number,
+            last_pmt.pmt_end_balance,
+            self.pmt_amount
+        );
+
+        let recast_pmts: Vec<LoanPayment> = add_scheduled_pmts(
+            &last_pmt.pmt_end_balance,
+            &last_pmt.pmt_date,
+            &get_next_pmt_date(
+                &last_pmt.pmt_nominal_date,
+                &self.pmt_schedule,
+                &self.pmt_date_rule,
+            ),
+            &self.annual_rate,
+            &self.pmt_schedule,
+            &self.pmt_date_rule,
+            &self.compound_type,
+            &self.dec_places,
+            self.pmt_amount,
+            &self.prepayment_plan.after_pmt(&last_pmt.pmt_number),
+        );
+
+        self.scheduled_pmts.truncate(pmts_made);
+        self.scheduled_pmts
+            .extend(recast_pmts.into_iter().map(|mut pmt| {
+                pmt.pmt_number += last_pmt.pmt_number;
+                pmt
+            }));
+    }
+
+    fn get_recast_scenario(&self, &recast_date: &NaiveDate) -> RecastScenario {
+        let pmt_amount = self
+            .scheduled_pmts
+            .iter()
+            .find(|pmt| pmt.pmt_date > recast_date)
+            .map_or(0., |pmt| pmt.pmt_amount - pmt.pmt_extra_principal);
+        RecastScenario {
+            pmt_amount: round(pmt_amount, self.dec_places),
+            pmt_count: self.scheduled_pmts.len(),
+            payoff_date: self
+                .scheduled_pmts
+                .last()
+                .map(|pmt| pmt.pmt_date)
+                .unwrap_or_default(),
+            total_interest: round(
+                self.scheduled_pmts
+                    .iter()
+                    .map(|pmt| pmt.pmt_interest_paid)
+                    .sum(),
+                self.dec_places,
+            ),
+        }
+    }
+
+    // compare recasting on recast_date with keeping the current payment and shortening the term
+    pub fn get_recast_comparison(&self, &recast_date: &NaiveDate) -> RecastComparison {
+        let mut recast = self.clone();
+        recast.recast(recast_date);
+
+        RecastComparison {
+            recast_date,
+            keep_pmt: self.get_recast_scenario(&recast_date),
+            recast: recast.get_recast_scenario(&recast_date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule, PrepaymentPlan};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn new_loan() -> Loan {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        let mut plan = PrepaymentPlan::default();
+        plan.add_one_time(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), 50000.);
+        loan.set_prepayment_plan(plan);
+        loan
+    }
+
+    #[test]
+    fn test_recast() {
+        let mut loan = new_loan();
+        let before = loan.get_scheduled_pmts()[11];
+        assert_eq!(before.pmt_extra_principal, 50000.);
+
+        let pmt_amount = loan.recast(NaiveDate::from_ymd_opt(2025, 3, 15).unwrap());
+        assert_eq!(pmt_amount, Some(1329.9563));
+        assert_eq!(loan.get_pmt_count(), 180);
+
+        // history before the recast is kept
+        let pmts = loan.get_scheduled_pmts();
+        assert_eq!(pmts[11], before);
+        assert_eq!(pmts[12].pmt_number, 13);
+        assert_eq!(pmts[12].pmt_amount, 1329.9563);
+        assert_eq!(
+            pmts[179].pmt_date,
+            NaiveDate::from_ymd_opt(2039, 3, 1).unwrap()
+        );
+        assert_eq!(pmts[179].pmt_end_balance, 0.);
+
+        // nothing to recast before the first payment
+        assert_eq!(
+            loan.recast(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recast_comparison() {
+        let loan = new_loan();
+        let comparison = loan.get_recast_comparison(&NaiveDate::from_ymd_opt(2025, 3, 15).unwrap());
+
+        assert_eq!(comparison.keep_pmt.pmt_amount, 1797.6565);
+        assert_eq!(comparison.recast.pmt_amount, 1329.9563);
+        assert_eq!(comparison.recast.pmt_count, 180);
+        assert!(comparison.keep_pmt.pmt_count < 180);
+        assert!(comparison.keep_pmt.total_interest < comparison.recast.total_interest);
+    }
+}
@@ -0,0 +1,67 @@
+use super::{round, Loan};
+use crate::cashflow::{xirr, CashFlow};
+
+impl Loan {
+    // cash flows from the lender's perspective: the amount financed goes out on the loan date,
+    // then each actual payment and remaining scheduled payment comes in, less any escrow and PMI
+    pub fn get_cash_flows(&self) -> Vec<CashFlow> {
+        let mut flows = vec![CashFlow::new(self.loan_date, -self.get_amount_financed())];
+        flows.extend(self.get_projected_pmts().iter().map(|pmt| {
+            CashFlow::new(
+                pmt.pmt_date,
+                pmt.pmt_amount - pmt.pmt_escrow_paid - pmt.pmt_pmi_paid,
+            )
+        }));
+        flows
+    }
+
+    // lender's yield as an effective annual rate (i.e., 7.25)
+    pub fn get_xirr(&self) -> Option<f64> {
+        xirr(&self.get_cash_flows()).map(|rate| round(rate, self.dec_places))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loan::{new_test_loan, Compounding, FeeAmount, FeeType, Loan, LoanFee, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_loan_xirr() {
+        let mut loan = new_test_loan(100000., 10., 8.);
+
+        // 8% compounded monthly is about 8.30% effective
+        let rate = loan.get_xirr().unwrap();
+        assert!((rate - 8.30).abs() < 0.01);
+
+        // points received raise the yield
+        loan.add_fee(LoanFee::new(
+            FeeType::DiscountPoints,
+            FeeAmount::Points(2.),
+            false,
+        ));
+        assert!(loan.get_xirr().unwrap() > rate);
+    }
+
+    #[test]
+    fn test_loan_xirr_late_pmts() {
+        let mut loan = new_test_loan(100000., 10., 8.);
+        let rate = loan.get_xirr().unwrap();
+
+        // a borrower who pays two months late on every payment lowers the realized yield
+        for pmt_number in 1..=12 {
+            let due = loan.get_scheduled_pmts()[pmt_number - 1].pmt_date;
+            loan.add_actual_pmt(
+                pmt_number as i32,
+                due.checked_add_months(chrono::Months::new(2)).unwrap(),
+                *loan.get_pmt_amount(),
+            );
+        }
+        assert_eq!(
+            loan.get_cash_flows()[1].cf_date,
+            NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()
+        );
+        assert!(loan.get_xirr().unwrap() < rate);
+    }
+}
@@ -0,0 +1,202 @@
+use super::{round, Loan, LoanPayment};
+use chrono::{Datelike, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct YearTotals {
+    pub year: i32,
+    pub pmt_count: usize,
+    pub total_pmts: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub end_balance: f64,
+}
+
+impl fmt::Display for YearTotals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "year {}, {} payments, total ${:.2}, interest ${:.2}, principal ${:.2}, ending balance ${:.2}",
+            self.year,
+            self.pmt_count,
+            self.total_pmts,
+            self.interest,
+            self.principal,
+            self.end_balance
+        )
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoanSummary {
+    pub pmt_count: usize,
+    pub total_pmts: f64,
+    pub total_interest: f64,
+    pub total_principal: f64,
+    pub maturity_date: NaiveDate,
+    pub avg_rate: f64, // interest paid as an annual rate on the average balance outstanding
+    pub calendar_years: Vec<YearTotals>,
+    // fiscal years are named for the calendar year they end in
+    pub fiscal_years: Vec<YearTotals>,
+}
+
+impl fmt::Display for LoanSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} payments, total ${:.2}, interest ${:.2}, principal ${:.2}, maturity {}, average rate {:.4}%",
+            self.pmt_count,
+            self.total_pmts,
+            self.total_interest,
+            self.total_principal,
+            self.maturity_date,
+            self.avg_rate
+        )?;
+        writeln!(f, "calendar years")?;
+        for year in &self.calendar_years {
+            writeln!(f, "  {}", year)?;
+        }
+        writeln!(f, "fiscal years")?;
+        for year in &self.fiscal_years {
+            writeln!(f, "  {}", year)?;
+        }
+        Ok(())
+    }
+}
+
+// subtotal payments by year. year_of assigns each payment date to a year.
+fn get_year_totals(
+    pmts: &[LoanPayment],
+    &dec_places: &f64,
+    year_of: impl Fn(&NaiveDate) -> i32,
+) -> Vec<YearTotals> {
+    let mut years: Vec<YearTotals> = Vec::new();
+    for pmt in pmts {
+        let year = year_of(&pmt.pmt_date);
+        if years.last().map(|totals| totals.year) != Some(year) {
+            years.push(YearTotals {
+                year,
+                ..Default::default()
+            });
+        }
+        let totals = years.last_mut().unwrap();
+        totals.pmt_count += 1;
+        totals.total_pmts += pmt.pmt_amount;
+        totals.interest += pmt.pmt_interest_paid;
+        totals.principal += pmt.pmt_principal_paid + pmt.pmt_extra_principal;
+        totals.end_balance = pmt.pmt_end_balance;
+    }
+
+    for totals in years.iter_mut() {
+        totals.total_pmts = round(totals.total_pmts, dec_places);
+        totals.interest = round(totals.interest, dec_places);
+        totals.principal = round(totals.principal, dec_places);
+    }
+    years
+}
+
+impl Loan {
+    // summarize the actual payments and remaining scheduled payments. fiscal_year_start is the
+    // month (1 - 12) the fiscal year begins.
+    pub fn get_summary(&self, &fiscal_year_start: &u32) -> LoanSummary {
+        let pmts = self.get_projected_pmts();
+
+        // balance-years outstanding, to express interest paid as an annual rate
+        let mut balance_years = 0.;
+        let mut begin_balance = self.get_note_amount();
+        let mut begin_date = self.loan_date;
+        for pmt in &pmts {
+            let days = pmt.pmt_date.signed_duration_since(begin_date).num_days() as f64;
+            balance_years += begin_balance * days / 365.;
+            begin_balance = pmt.pmt_end_balance;
+            begin_date = pmt.pmt_date;
+        }
+
+        let total_interest: f64 = pmts.iter().map(|pmt| pmt.pmt_interest_paid).sum();
+        let fiscal_year = |date: &NaiveDate| -> i32 {
+            if fiscal_year_start > 1 && date.month() >= fiscal_year_start {
+                date.year() + 1
+            } else {
+                date.year()
+            }
+        };
+
+        LoanSummary {
+            pmt_count: pmts.len(),
+            total_pmts: round(pmts.iter().map(|pmt| pmt.pmt_amount).sum(), self.dec_places),
+            total_interest: round(total_interest, self.dec_places),
+            total_principal: round(
+                pmts.iter()
+                    .map(|pmt| pmt.pmt_principal_paid + pmt.pmt_extra_principal)
+                    .sum(),
+                self.dec_places,
+            ),
+            maturity_date: pmts.last().map(|pmt| pmt.pmt_date).unwrap_or_default(),
+            avg_rate: if balance_years > 0. {
+                round(total_interest / balance_years * 100., self.dec_places)
+            } else {
+                0.
+            },
+            calendar_years: get_year_totals(&pmts, &self.dec_places, |date| date.year()),
+            fiscal_years: get_year_totals(&pmts, &self.dec_places, fiscal_year),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_summary() {
+        let loan = new_test_loan(200000., 15., 7.);
+        let summary = loan.get_summary(&10);
+
+        assert_eq!(summary.pmt_count, 180);
+        assert_eq!(summary.total_interest, 123578.1826);
+        assert!((summary.total_principal - 200000.).abs() < 0.01);
+        assert!((summary.avg_rate - 7.).abs() < 0.1);
+        assert_eq!(
+            summary.maturity_date,
+            NaiveDate::from_ymd_opt(2039, 3, 1).unwrap()
+        );
+
+        // April through December 2024
+        assert_eq!(summary.calendar_years.len(), 16);
+        assert_eq!(summary.calendar_years[0].year, 2024);
+        assert_eq!(summary.calendar_years[0].pmt_count, 9);
+        assert_eq!(summary.calendar_years[1].pmt_count, 12);
+
+        // fiscal year 2024 ends in September 2024
+        assert_eq!(summary.fiscal_years[0].year, 2024);
+        assert_eq!(summary.fiscal_years[0].pmt_count, 6);
+        assert_eq!(summary.fiscal_years[1].year, 2025);
+        assert_eq!(summary.fiscal_years[1].pmt_count, 12);
+
+        let interest: f64 = summary
+            .calendar_years
+            .iter()
+            .map(|year| year.interest)
+            .sum();
+        assert!((interest - summary.total_interest).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_summary_with_actual_pmts() {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1797.6565);
+        loan.add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), 11797.6565);
+
+        let summary = loan.get_summary(&1);
+        assert!(summary.pmt_count < 180);
+        assert_eq!(summary.calendar_years, summary.fiscal_years);
+        assert!((summary.total_principal - 200000.).abs() < 0.01);
+        assert!(summary.total_interest < 123578.1826);
+    }
+}
@@ -0,0 +1,158 @@
+use super::{round, Loan};
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// value of the remaining payments of a loan at a market discount rate
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LoanValuation {
+    pub as_of: NaiveDate,
+    pub discount_rate: f64,
+    pub par: f64,               // loan balance as of the valuation date
+    pub accrued_interest: f64,  // interest accrued since the last payment
+    pub price: f64,             // present value of the remaining payments
+    pub premium: f64,           // price less accrued interest and par; negative for a discount
+    pub price_pct: f64,         // price less accrued interest as a percent of par
+    pub macaulay_duration: f64, // in years
+    pub modified_duration: f64, // in years
+    pub convexity: f64,
+}
+
+impl fmt::Display for LoanValuation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "as of {} at {:.4}%: par ${:.2}, accrued interest ${:.2}, price ${:.2} ({:.4}% of par), premium ${:.2}, duration {:.4} (modified {:.4}), convexity {:.4}",
+            self.as_of,
+            self.discount_rate,
+            self.par,
+            self.accrued_interest,
+            self.price,
+            self.price_pct,
+            self.premium,
+            self.macaulay_duration,
+            self.modified_duration,
+            self.convexity
+        )
+    }
+}
+
+impl Loan {
+    // value the payments due after as_of at discount_rate, an effective annual rate (i.e., 9.0).
+    // The price includes any interest accrued since the last payment, which par does not, so the
+    // premium and price percent are figured on the price less the accrued interest.
+    pub fn get_valuation(&self, &as_of: &NaiveDate, &discount_rate: &f64) -> LoanValuation {
+        let rate = discount_rate / 100.;
+        let pmts = self.get_projected_pmts();
+
+        // the next payment's interest, pro-rated by the days of its period elapsed
+        let next = pmts.iter().position(|pmt| pmt.pmt_date > as_of);
+        let accrued_interest = next.map_or(0., |next| {
+            let begin_date = match next {
+                0 => self.loan_date,
+                _ => pmts[next - 1].pmt_date,
+            };
+            let period_days = pmts[next]
+                .pmt_date
+                .signed_duration_since(begin_date)
+                .num_days();
+            let elapsed_days = as_of.signed_duration_since(begin_date).num_days().max(0);
+            pmts[next].pmt_interest_paid * elapsed_days as f64 / period_days.max(1) as f64
+        });
+
+        let flows: Vec<(f64, f64)> = pmts
+            .iter()
+            .filter(|pmt| pmt.pmt_date > as_of)
+            .map(|pmt| {
+                (
+                    pmt.pmt_date.signed_duration_since(as_of).num_days() as f64 / 365.,
+                    pmt.pmt_amount - pmt.pmt_escrow_paid - pmt.pmt_pmi_paid,
+                )
+            })
+            .collect();
+
+        let mut price = 0.;
+        let mut weighted_time = 0.;
+        let mut weighted_convexity = 0.;
+        for (years, amount) in &flows {
+            let present_value = amount / (1. + rate).powf(*years);
+            price += present_value;
+            weighted_time += years * present_value;
+            weighted_convexity += years * (years + 1.) * present_value / (1. + rate).powi(2);
+        }
+
+        let par = self.get_balance(&as_of);
+        let (macaulay_duration, convexity) = if price > 0. {
+            (weighted_time / price, weighted_convexity / price)
+        } else {
+            (0., 0.)
+        };
+
+        let clean_price = price - accrued_interest;
+        LoanValuation {
+            as_of,
+            discount_rate,
+            par,
+            accrued_interest: round(accrued_interest, self.dec_places),
+            price: round(price, self.dec_places),
+            premium: round(clean_price - par, self.dec_places),
+            price_pct: if par > 0. {
+                round(clean_price / par * 100., self.dec_places)
+            } else {
+                0.
+            },
+            macaulay_duration: round(macaulay_duration, self.dec_places),
+            modified_duration: round(macaulay_duration / (1. + rate), self.dec_places),
+            convexity: round(convexity, self.dec_places),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loan::{new_test_loan, Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    #[test]
+    fn test_valuation() {
+        let loan = new_test_loan(100000., 10., 8.);
+        let as_of = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        // discounting at the loan's own effective rate prices the note close to par
+        let valuation = loan.get_valuation(&as_of, &8.2999);
+        assert_eq!(valuation.par, loan.get_balance(&as_of));
+        assert!(valuation.price_pct > 99.9 && valuation.price_pct < 100.1);
+
+        // a higher market rate prices the note at a discount
+        let discount = loan.get_valuation(&as_of, &10.);
+        assert!(discount.premium < 0.);
+        assert!(discount.price < valuation.price);
+        assert!(discount.macaulay_duration > 3. && discount.macaulay_duration < 5.);
+        assert!(discount.modified_duration < discount.macaulay_duration);
+        assert!(discount.convexity > 0.);
+
+        // duration predicts the price change for a small rate change
+        let bumped = loan.get_valuation(&as_of, &10.01);
+        let predicted = -discount.modified_duration * 0.0001 * discount.price;
+        assert!(((bumped.price - discount.price) - predicted).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_valuation_between_pmts() {
+        let loan = new_test_loan(100000., 10., 8.);
+        let as_of = NaiveDate::from_ymd_opt(2025, 3, 16).unwrap();
+
+        // half a month of interest has accrued on the balance after the March payment
+        let valuation = loan.get_valuation(&as_of, &8.2999);
+        let expected = loan.get_balance(&as_of) * 0.08 / 12. * 15. / 31.;
+        assert!((valuation.accrued_interest - expected).abs() < 0.01);
+        assert!(valuation.price > valuation.par);
+
+        // the premium excludes the accrued interest, so the note is still priced close to par
+        assert!(valuation.price_pct > 99.9 && valuation.price_pct < 100.1);
+        assert!(valuation.premium.abs() < 100.);
+    }
+}
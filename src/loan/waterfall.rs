@@ -0,0 +1,258 @@
+use super::{get_period_interest_rate, round, Compounding, Loan, LoanPayment};
+use chrono::NaiveDate;
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// the parts of the loan an actual payment can be applied to
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PmtBucket {
+    #[default]
+    Fees, // outstanding late fees
+    Escrow,         // escrow deposit due with the payment
+    Pmi,            // mortgage insurance premium due with the payment
+    Interest,       // interest accrued since the last payment, including unpaid interest
+    Principal,      // principal portion of the scheduled payment
+    ExtraPrincipal, // anything left over is a principal curtailment
+}
+
+// the usual servicer order: fees, escrow, PMI, interest, scheduled principal, then extra principal
+pub fn default_pmt_waterfall() -> Vec<PmtBucket> {
+    vec![
+        PmtBucket::Fees,
+        PmtBucket::Escrow,
+        PmtBucket::Pmi,
+        PmtBucket::Interest,
+        PmtBucket::Principal,
+        PmtBucket::ExtraPrincipal,
+    ]
+}
+
+impl Loan {
+    // set the order in which actual payments are applied. Buckets left out of the order
+    // receive nothing.
+    pub fn set_pmt_waterfall(&mut self, pmt_waterfall: Vec<PmtBucket>) {
+        self.pmt_waterfall = pmt_waterfall;
+        self.recalc_actual_pmts();
+    }
+
+    pub fn get_pmt_waterfall(&self) -> &[PmtBucket] {
+        &self.pmt_waterfall
+    }
+
+    // interest rate from begin_date to pmt_date for an actual payment on scheduled payment
+    // pmt_number. Daily compounding and simple interest accrue by the day. Otherwise each
+    // scheduled period is charged once, by the first payment toward it, so a period paid in parts
+    // accrues one period of interest; periods_charged counts the periods charged so far.
+    fn get_actual_interest_rate(
+        &self,
+        &pmt_number: &i32,
+        &begin_date: &NaiveDate,
+        &pmt_date: &NaiveDate,
+        periods_charged: &mut i32,
+    ) -> f64 {
+        if matches!(self.compound_type, Compounding::Daily | Compounding::Simple) {
+            return get_period_interest_rate(
+                &self.annual_rate,
+                &self.pmt_schedule,
+                &self.compound_type,
+                &begin_date,
+                &pmt_date,
+            );
+        }
+
+        let mut rate = 0.;
+        while *periods_charged < pmt_number {
+            *periods_charged += 1;
+            let period = *periods_charged as usize;
+            let period_begin = match period {
+                1 => Some(self.loan_date),
+                _ => self.scheduled_pmts.get(period - 2).map(|pmt| pmt.pmt_date),
+            };
+            let period_end = self.scheduled_pmts.get(period - 1).map(|pmt| pmt.pmt_date);
+            rate += get_period_interest_rate(
+                &self.annual_rate,
+                &self.pmt_schedule,
+                &self.compound_type,
+                &period_begin.unwrap_or(begin_date),
+                &period_end.unwrap_or(pmt_date),
+            );
+        }
+        rate
+    }
+
+    // recalculate all actual payments in date order, applying each through the payment waterfall.
+    // Unpaid fees and interest carry forward to the next payment; they are not capitalized.
+    pub(super) fn recalc_actual_pmts(&mut self) {
+        self.actual_pmts.sort_by_key(|pmt| pmt.pmt_date);
+        self.assess_late_fees();
+
+        let dec_places = self.dec_places;
+        let escrow_due = self.get_escrow_pmt();
+        let pmi_pmt = self.get_pmi_pmt();
+        let pmi_termination_date = self.get_pmi_termination_date();
+        let mut begin_date = self.loan_date;
+        let mut balance = self.get_note_amount();
+        let mut unpaid_interest = 0.;
+        let mut fees_paid = 0.;
+        let mut periods_charged = 0;
+
+        let mut actual_pmts = std::mem::take(&mut self.actual_pmts);
+        for pmt in actual_pmts.iter_mut() {
+            let interest_due = unpaid_interest
+                + balance
+                    * self.get_actual_interest_rate(
+                        &pmt.pmt_number,
+                        &begin_date,
+                        &pmt.pmt_date,
+                        &mut periods_charged,
+                    );
+            let pmi_due = match pmi_termination_date {
+                Some(termination_date) if pmt.pmt_date < termination_date => pmi_pmt,
+                _ => 0.,
+            };
+            let fees_due = (self.get_late_fee_total(&pmt.pmt_date) - fees_paid).max(0.);
+            let sched_amount = self
+                .scheduled_pmts
+                .get(pmt.pmt_number as usize - 1)
+                .map_or(0., |sched| sched.pmt_amount - sched.pmt_extra_principal);
+            let principal_due = (sched_amount - interest_due).clamp(0., balance);
+
+            let mut remaining = pmt.pmt_amount;
+            let mut apply = |due: f64| -> f64 {
+                let applied = round(due.min(remaining).max(0.), dec_places);
+                remaining -= applied;
+                applied
+            };
+
+            pmt.pmt_fees_paid = 0.;
+            pmt.pmt_escrow_paid = 0.;
+            pmt.pmt_pmi_paid = 0.;
+            pmt.pmt_interest_paid = 0.;
+            pmt.pmt_principal_paid = 0.;
+            pmt.pmt_extra_principal = 0.;
+            for bucket in &self.pmt_waterfall {
+                match bucket {
+                    PmtBucket::Fees => pmt.pmt_fees_paid = apply(fees_due),
+                    PmtBucket::Escrow => pmt.pmt_escrow_paid = apply(escrow_due),
+                    PmtBucket::Pmi => pmt.pmt_pmi_paid = apply(pmi_due),
+                    PmtBucket::Interest => pmt.pmt_interest_paid = apply(interest_due),
+                    PmtBucket::Principal => {
+                        pmt.pmt_principal_paid =
+                            apply(principal_due.min(balance - pmt.pmt_extra_principal))
+                    }
+                    PmtBucket::ExtraPrincipal => {
+                        pmt.pmt_extra_principal = apply(balance - pmt.pmt_principal_paid)
+                    }
+                }
+            }
+            if remaining > 0. {
+                trace!(
+                    "pmt # {} on {} has ${} unapplied",
+                    pmt.pmt_number,
+                    pmt.pmt_date,
+                    remaining
+                );
+            }
+
+            fees_paid += pmt.pmt_fees_paid;
+            unpaid_interest = (interest_due - pmt.pmt_interest_paid).max(0.);
+            balance -= pmt.pmt_principal_paid + pmt.pmt_extra_principal;
+            pmt.pmt_end_balance = round(balance, dec_places);
+            begin_date = pmt.pmt_date;
+        }
+        self.actual_pmts = actual_pmts;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PmtBucket;
+    use crate::loan::{new_test_loan, Compounding, LateFeeRule, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn new_loan() -> Loan {
+        let mut loan = new_test_loan(200000., 15., 7.);
+        loan.set_late_fee_terms(15, LateFeeRule::Flat(50.));
+        loan
+    }
+
+    #[test]
+    fn test_default_waterfall() {
+        let mut loan = new_loan();
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 2000.)
+            .unwrap();
+        assert_eq!(pmt.pmt_fees_paid, 0.);
+        assert_eq!(pmt.pmt_interest_paid, 1166.6667);
+        assert_eq!(pmt.pmt_principal_paid, 630.9898);
+        assert_eq!(pmt.pmt_extra_principal, 202.3435);
+        assert_eq!(pmt.pmt_end_balance, 199166.6667);
+
+        // late payment pays the late fee first
+        let pmt = loan
+            .add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 5, 20).unwrap(), 1847.6565)
+            .unwrap();
+        assert_eq!(pmt.pmt_fees_paid, 50.);
+        assert_eq!(pmt.pmt_interest_paid, 1161.8056);
+        assert_eq!(pmt.pmt_principal_paid, 635.8509);
+        assert_eq!(pmt.pmt_extra_principal, 0.);
+    }
+
+    #[test]
+    fn test_short_pmt_carries_interest() {
+        let mut loan = new_loan();
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1000.)
+            .unwrap();
+        assert_eq!(pmt.pmt_interest_paid, 1000.);
+        assert_eq!(pmt.pmt_principal_paid, 0.);
+        assert_eq!(pmt.pmt_end_balance, 200000.);
+
+        // the unpaid interest is collected before principal on the next payment
+        let pmt = loan
+            .add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), 1797.6565)
+            .unwrap();
+        assert_eq!(pmt.pmt_interest_paid, 1333.3333);
+        assert_eq!(pmt.pmt_principal_paid, 464.3232);
+    }
+
+    #[test]
+    fn test_split_pmt() {
+        // one period's payment made in two parts accrues one period of interest
+        let mut loan = new_loan();
+        loan.add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), 1000.)
+            .unwrap();
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 10).unwrap(), 797.6565)
+            .unwrap();
+        assert_eq!(pmt.pmt_interest_paid, 166.6667);
+        assert_eq!(pmt.pmt_principal_paid, 630.9898);
+        assert_eq!(pmt.pmt_end_balance, 199369.0102);
+
+        // the next period accrues interest as usual
+        let pmt = loan
+            .add_actual_pmt(2, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), 1797.6565)
+            .unwrap();
+        assert_eq!(pmt.pmt_interest_paid, 1162.9859);
+    }
+
+    #[test]
+    fn test_custom_waterfall() {
+        let mut loan = new_loan();
+        loan.set_pmt_waterfall(vec![
+            PmtBucket::Interest,
+            PmtBucket::Principal,
+            PmtBucket::Fees,
+        ]);
+        let pmt = loan
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2024, 4, 20).unwrap(), 1800.)
+            .unwrap();
+        assert_eq!(pmt.pmt_interest_paid, 1166.6667);
+        assert_eq!(pmt.pmt_principal_paid, 630.9898);
+        assert_eq!(pmt.pmt_fees_paid, 2.3435);
+        assert_eq!(pmt.pmt_extra_principal, 0.);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::loan::{round, Loan, LoanPayment};
+use chrono::{Datelike, Months, NaiveDate};
+use log::warn;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// a payment on one of the portfolio's loans
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PortfolioPayment {
+    pub loan_name: String,
+    pub pmt: LoanPayment,
+}
+
+impl fmt::Display for PortfolioPayment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.loan_name, self.pmt)
+    }
+}
+
+// payments due in the month beginning month_date, and the balance of all loans at month end
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MonthlyTotal {
+    pub month_date: NaiveDate,
+    pub pmt_count: usize,
+    pub pmt_amount: f64,
+    pub principal_paid: f64,
+    pub interest_paid: f64,
+    pub end_balance: f64,
+}
+
+impl fmt::Display for MonthlyTotal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} payments ${:.2}, principal ${:.2}, interest ${:.2}, ending balance ${:.2}",
+            self.month_date.format("%Y-%m"),
+            self.pmt_count,
+            self.pmt_amount,
+            self.principal_paid,
+            self.interest_paid,
+            self.end_balance
+        )
+    }
+}
+
+// loans held together under unique names, such as a household's mortgage, auto and student loans
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Portfolio {
+    loans: BTreeMap<String, Loan>,
+    pub dec_places: f64,
+}
+
+impl Portfolio {
+    pub fn new(dec_places: f64) -> Self {
+        Self {
+            loans: BTreeMap::new(),
+            dec_places,
+        }
+    }
+
+    // add a loan. Returns None if the name is already in use.
+    pub fn add_loan(&mut self, name: &str, loan: Loan) -> Option<&Loan> {
+        if self.loans.contains_key(name) {
+            warn!("portfolio already has a loan named {}", name);
+            return None;
+        }
+        Some(self.loans.entry(name.to_string()).or_insert(loan))
+    }
+
+    pub fn remove_loan(&mut self, name: &str) -> Option<Loan> {
+        self.loans.remove(name)
+    }
+
+    pub fn get_loan(&self, name: &str) -> Option<&Loan> {
+        self.loans.get(name)
+    }
+
+    // for recording actual payments on a loan in the portfolio
+    pub fn get_loan_mut(&mut self, name: &str) -> Option<&mut Loan> {
+        self.loans.get_mut(name)
+    }
+
+    pub fn get_loan_names(&self) -> Vec<&str> {
+        self.loans.keys().map(|name| name.as_str()).collect()
+    }
+
+    // payments on all loans due from begin_date through end_date, by date and then loan name.
+    // Each loan's payments are its actual payments followed by its projected payments.
+    pub fn get_calendar(
+        &self,
+        &begin_date: &NaiveDate,
+        &end_date: &NaiveDate,
+    ) -> Vec<PortfolioPayment> {
+        let mut calendar: Vec<PortfolioPayment> = self
+            .loans
+            .iter()
+            .flat_map(|(name, loan)| {
+                loan.get_projected_pmts()
+                    .into_iter()
+                    .filter(|pmt| pmt.pmt_date >= begin_date && pmt.pmt_date <= end_date)
+                    .map(|pmt| PortfolioPayment {
+                        loan_name: name.clone(),
+                        pmt,
+                    })
+            })
+            .collect();
+        // the sort is stable, so payments on the same date stay in loan name order
+        calendar.sort_by_key(|entry| entry.pmt.pmt_date);
+        calendar
+    }
+
+    // totals for each month from the month of begin_date through the month of end_date
+    pub fn get_monthly_totals(
+        &self,
+        &begin_date: &NaiveDate,
+        &end_date: &NaiveDate,
+    ) -> Vec<MonthlyTotal> {
+        let projected: Vec<(&Loan, Vec<LoanPayment>)> = self
+            .loans
+            .values()
+            .map(|loan| (loan, loan.get_projected_pmts()))
+            .collect();
+
+        let mut totals = Vec::new();
+        let mut month_date = begin_date.with_day(1).unwrap();
+        while month_date <= end_date {
+            let next_month = month_date.checked_add_months(Months::new(1)).unwrap();
+            let month_end = next_month.pred_opt().unwrap();
+            let mut total = MonthlyTotal {
+                month_date,
+                ..Default::default()
+            };
+            for (loan, pmts) in &projected {
+                for pmt in pmts
+                    .iter()
+                    .filter(|pmt| pmt.pmt_date >= month_date && pmt.pmt_date <= month_end)
+                {
+                    total.pmt_count += 1;
+                    total.pmt_amount += pmt.pmt_amount;
+                    total.principal_paid += pmt.pmt_principal_paid + pmt.pmt_extra_principal;
+                    total.interest_paid += pmt.pmt_interest_paid;
+                }
+                total.end_balance += get_projected_balance(loan, pmts, &month_end);
+            }
+            total.pmt_amount = round(total.pmt_amount, self.dec_places);
+            total.principal_paid = round(total.principal_paid, self.dec_places);
+            total.interest_paid = round(total.interest_paid, self.dec_places);
+            total.end_balance = round(total.end_balance, self.dec_places);
+            totals.push(total);
+            month_date = next_month;
+        }
+        totals
+    }
+
+    // the combined balance of the loans made on or before as_of
+    pub fn get_balance(&self, &as_of: &NaiveDate) -> f64 {
+        round(
+            self.loans
+                .values()
+                .filter(|loan| loan.loan_date <= as_of)
+                .map(|loan| loan.get_balance(&as_of))
+                .sum(),
+            self.dec_places,
+        )
+    }
+
+    // balances of each loan as of a date, by loan name
+    pub fn get_balances(&self, &as_of: &NaiveDate) -> Vec<(&str, f64)> {
+        self.loans
+            .iter()
+            .filter(|(_, loan)| loan.loan_date <= as_of)
+            .map(|(name, loan)| (name.as_str(), loan.get_balance(&as_of)))
+            .collect()
+    }
+}
+
+// a loan's balance as of a date, or nothing before it is made
+fn get_projected_balance(loan: &Loan, pmts: &[LoanPayment], &as_of: &NaiveDate) -> f64 {
+    if loan.loan_date > as_of {
+        return 0.;
+    }
+    loan.get_balance_from(pmts, &as_of)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Portfolio;
+    use crate::loan::{Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn date(yr: i32, mon: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yr, mon, day).unwrap()
+    }
+
+    fn new_portfolio() -> Portfolio {
+        let mut portfolio = Portfolio::new(2.);
+        portfolio.add_loan(
+            "mortgage",
+            Loan::new(
+                300000.,
+                30.,
+                6.5,
+                PmtSchedule::Monthly,
+                Compounding::Monthly,
+                date(2024, 1, 1),
+                date(2024, 2, 1),
+                2.,
+            ),
+        );
+        portfolio.add_loan(
+            "auto",
+            Loan::new(
+                30000.,
+                5.,
+                7.,
+                PmtSchedule::Monthly,
+                Compounding::Monthly,
+                date(2024, 3, 15),
+                date(2024, 4, 15),
+                2.,
+            ),
+        );
+        portfolio
+    }
+
+    #[test]
+    fn test_calendar() {
+        let mut portfolio = new_portfolio();
+        assert_eq!(portfolio.get_loan_names(), vec!["auto", "mortgage"]);
+        let duplicate = portfolio.get_loan("auto").unwrap().clone();
+        assert_eq!(portfolio.add_loan("auto", duplicate), None);
+
+        let calendar = portfolio.get_calendar(&date(2024, 3, 1), &date(2024, 5, 31));
+        let names: Vec<&str> = calendar
+            .iter()
+            .map(|entry| entry.loan_name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["mortgage", "mortgage", "auto", "mortgage", "auto"]
+        );
+        assert!(calendar
+            .windows(2)
+            .all(|pair| pair[0].pmt.pmt_date <= pair[1].pmt.pmt_date));
+        assert_eq!(calendar[2].pmt.pmt_date, date(2024, 4, 15));
+
+        assert!(portfolio.remove_loan("auto").is_some());
+        assert_eq!(
+            portfolio
+                .get_calendar(&date(2024, 3, 1), &date(2024, 5, 31))
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_monthly_totals() {
+        let portfolio = new_portfolio();
+        let totals = portfolio.get_monthly_totals(&date(2024, 2, 10), &date(2024, 5, 1));
+        assert_eq!(totals.len(), 4);
+        assert_eq!(totals[0].month_date, date(2024, 2, 1));
+
+        // the auto loan is not on the books until March
+        assert_eq!(totals[0].pmt_count, 1);
+        assert_eq!(
+            totals[0].end_balance,
+            portfolio.get_balance(&date(2024, 2, 29))
+        );
+        assert_eq!(
+            totals[1].end_balance,
+            portfolio.get_balance(&date(2024, 3, 31))
+        );
+        assert!(totals[1].end_balance > totals[0].end_balance + 29000.);
+        assert_eq!(portfolio.get_balances(&date(2024, 3, 1)).len(), 1);
+
+        let april = totals[2];
+        assert_eq!(april.pmt_count, 2);
+        assert!((april.principal_paid + april.interest_paid - april.pmt_amount).abs() < 0.01);
+        assert!(totals[3].end_balance < april.end_balance);
+    }
+}
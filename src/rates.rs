@@ -0,0 +1,133 @@
+use crate::loan::{get_compounding_periods, get_pmt_schedule, Compounding, PmtSchedule};
+
+// interest rate (as a decimal) for one of pmt_periods equal periods in a year, given a nominal
+// annual rate (i.e., 7.0) compounded per compound_type
+pub(crate) fn get_period_rate(
+    &annual_rate: &f64,
+    &compound_type: &Compounding,
+    &pmt_periods: &f64,
+) -> f64 {
+    let rate = annual_rate / 100.;
+    let compounding_periods = get_compounding_periods(compound_type);
+
+    match compound_type {
+        Compounding::Continuous => (rate / pmt_periods).exp() - 1.,
+        Compounding::Simple => rate / pmt_periods,
+        _ if compounding_periods == pmt_periods => rate / compounding_periods,
+        _ => (1. + rate / compounding_periods).powf(compounding_periods / pmt_periods) - 1.,
+    }
+}
+
+// effective annual rate of a nominal annual rate. Rates are percents (i.e., 7.0).
+pub fn get_effective_annual_rate(&nominal_rate: &f64, &compound_type: &Compounding) -> f64 {
+    get_period_rate(&nominal_rate, &compound_type, &1.) * 100.
+}
+
+// nominal annual rate, compounded per compound_type, with the given effective annual rate
+pub fn get_nominal_rate(&effective_rate: &f64, &compound_type: &Compounding) -> f64 {
+    let rate = effective_rate / 100.;
+    let compounding_periods = get_compounding_periods(compound_type);
+
+    match compound_type {
+        Compounding::Continuous => (1. + rate).ln() * 100.,
+        Compounding::Simple => effective_rate,
+        _ => compounding_periods * ((1. + rate).powf(1. / compounding_periods) - 1.) * 100.,
+    }
+}
+
+// restate a nominal annual rate under a different compounding frequency
+pub fn convert_nominal_rate(
+    &nominal_rate: &f64,
+    &from_compounding: &Compounding,
+    &to_compounding: &Compounding,
+) -> f64 {
+    get_nominal_rate(
+        &get_effective_annual_rate(&nominal_rate, &from_compounding),
+        &to_compounding,
+    )
+}
+
+// rate charged each payment period for a nominal annual rate
+pub fn get_periodic_rate(
+    &nominal_rate: &f64,
+    &compound_type: &Compounding,
+    &pmt_schedule: &PmtSchedule,
+) -> Option<f64> {
+    get_pmt_schedule(pmt_schedule)
+        .map(|pmt_periods| get_period_rate(&nominal_rate, &compound_type, &pmt_periods) * 100.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        convert_nominal_rate, get_effective_annual_rate, get_nominal_rate, get_periodic_rate,
+    };
+    use crate::loan::{Compounding, PmtSchedule};
+    use test_log::test;
+
+    fn assert_close(left: f64, right: f64) {
+        assert!((left - right).abs() < 1e-6, "{} != {}", left, right);
+    }
+
+    #[test]
+    fn test_effective_annual_rate() {
+        assert_close(get_effective_annual_rate(&7., &Compounding::Annually), 7.);
+        assert_close(
+            get_effective_annual_rate(&7., &Compounding::SemiAnnually),
+            7.1225,
+        );
+        assert_close(
+            get_effective_annual_rate(&7., &Compounding::Monthly),
+            7.229008,
+        );
+        assert_close(
+            get_effective_annual_rate(&7., &Compounding::Daily),
+            7.250098,
+        );
+        assert_close(
+            get_effective_annual_rate(&7., &Compounding::Continuous),
+            7.250818,
+        );
+    }
+
+    #[test]
+    fn test_nominal_rate() {
+        for compound_type in [
+            Compounding::Daily,
+            Compounding::Monthly,
+            Compounding::Quarterly,
+            Compounding::SemiAnnually,
+            Compounding::Annually,
+            Compounding::Continuous,
+        ] {
+            let effective = get_effective_annual_rate(&6.5, &compound_type);
+            assert_close(get_nominal_rate(&effective, &compound_type), 6.5);
+        }
+
+        // a Canadian 6% compounded semi-annually restated as monthly compounding
+        assert_close(
+            convert_nominal_rate(&6., &Compounding::SemiAnnually, &Compounding::Monthly),
+            5.926346,
+        );
+    }
+
+    #[test]
+    fn test_periodic_rate() {
+        assert_close(
+            get_periodic_rate(&6., &Compounding::Monthly, &PmtSchedule::Monthly).unwrap(),
+            0.5,
+        );
+        assert_close(
+            get_periodic_rate(&6., &Compounding::SemiAnnually, &PmtSchedule::Monthly).unwrap(),
+            0.493862,
+        );
+        assert_close(
+            get_periodic_rate(&6., &Compounding::Continuous, &PmtSchedule::Quarterly).unwrap(),
+            1.511306,
+        );
+        assert_eq!(
+            get_periodic_rate(&6., &Compounding::Monthly, &PmtSchedule::Custom),
+            None
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use crate::cashflow::{xnpv, CashFlow};
+use crate::loan::{round, FeeAmount, FeeType, Loan, LoanFee, LoanPayment};
+use chrono::NaiveDate;
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// a proposed loan that pays off the balance of an existing loan on refi_date. Closing costs are
+// either rolled into the new loan or paid in cash.
+pub fn new_refinance_loan(
+    existing: &Loan,
+    refi_date: NaiveDate,
+    first_pmt_date: NaiveDate,
+    term: f64,
+    annual_rate: f64,
+    closing_costs: f64,
+    financed: bool,
+) -> Loan {
+    let mut proposed = Loan::new(
+        existing.get_balance(&refi_date),
+        term,
+        annual_rate,
+        existing.pmt_schedule,
+        existing.compound_type,
+        refi_date,
+        first_pmt_date,
+        existing.dec_places,
+    );
+    if closing_costs > 0. {
+        proposed.add_fee(LoanFee::new(
+            FeeType::ClosingCost,
+            FeeAmount::Flat(closing_costs),
+            financed,
+        ));
+    }
+    proposed
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RefiComparison {
+    pub refi_date: NaiveDate,
+    pub existing_balance: f64,
+    pub existing_pmt: f64,
+    pub proposed_pmt: f64,
+    pub pmt_difference: f64,    // existing payment less proposed payment
+    pub existing_interest: f64, // interest remaining on the existing loan after refi_date
+    pub proposed_interest: f64,
+    pub closing_costs: f64,
+    pub cash_costs: f64,   // closing costs paid in cash rather than financed
+    pub existing_npc: f64, // net present cost of the remaining payments
+    pub proposed_npc: f64, // net present cost of the payments plus closing costs paid in cash
+    pub breakeven_date: Option<NaiveDate>, // date the payment savings recover the cash costs
+}
+
+impl RefiComparison {
+    // compare keeping an existing loan with refinancing into a proposed loan dated on the refi
+    // date. The existing loan's remaining payments are projected from its actual payments, like
+    // its balance. Payments are discounted by date to the refi date at discount_rate, an effective
+    // annual rate (i.e. 5.0). Financed closing costs are already in the proposed payments, so
+    // only the costs paid in cash need to be recovered by the savings.
+    pub fn new(existing: &Loan, proposed: &Loan, &discount_rate: &f64) -> Self {
+        let refi_date = proposed.loan_date;
+        let dec_places = existing.dec_places;
+        let existing_pmts: Vec<LoanPayment> = existing
+            .get_projected_pmts()
+            .into_iter()
+            .filter(|pmt| pmt.pmt_date > refi_date)
+            .collect();
+        let proposed_pmts = proposed.get_scheduled_pmts();
+
+        let cash_costs: f64 = proposed
+            .get_fees()
+            .iter()
+            .filter(|fee| !fee.financed)
+            .map(|fee| fee.get_amount(&proposed.principal))
+            .sum();
+        let present_cost = |initial: f64, pmts: &mut dyn Iterator<Item = &LoanPayment>| -> f64 {
+            let mut flows = vec![CashFlow::new(refi_date, initial)];
+            flows.extend(pmts.map(|pmt| CashFlow::new(pmt.pmt_date, pmt.pmt_amount)));
+            xnpv(&discount_rate, &flows)
+        };
+        let existing_npc = present_cost(0., &mut existing_pmts.iter());
+        let proposed_npc = present_cost(cash_costs, &mut proposed_pmts.iter());
+
+        // cumulative payment savings by date against the closing costs paid in cash. The costs
+        // are recovered on the date the savings reach them for good.
+        let mut savings_by_date: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        for pmt in &existing_pmts {
+            *savings_by_date.entry(pmt.pmt_date).or_default() += pmt.pmt_amount;
+        }
+        for pmt in proposed_pmts {
+            *savings_by_date.entry(pmt.pmt_date).or_default() -= pmt.pmt_amount;
+        }
+        let mut savings = 0.;
+        let mut breakeven_date = None;
+        for (pmt_date, amount) in savings_by_date {
+            savings += amount;
+            if savings <= 0. || savings < cash_costs {
+                breakeven_date = None;
+            } else if breakeven_date.is_none() {
+                breakeven_date = Some(pmt_date);
+            }
+        }
+        trace!(
+            "refi on {}, cash costs {}, breakeven {:?}",
+            refi_date,
+            cash_costs,
+            breakeven_date
+        );
+
+        let existing_pmt = existing_pmts.first().map_or(0., |pmt| pmt.pmt_amount);
+        Self {
+            refi_date,
+            existing_balance: existing.get_balance(&refi_date),
+            existing_pmt,
+            proposed_pmt: *proposed.get_pmt_amount(),
+            pmt_difference: round(existing_pmt - proposed.get_pmt_amount(), dec_places),
+            existing_interest: round(
+                existing_pmts.iter().map(|pmt| pmt.pmt_interest_paid).sum(),
+                dec_places,
+            ),
+            proposed_interest: round(
+                proposed_pmts.iter().map(|pmt| pmt.pmt_interest_paid).sum(),
+                dec_places,
+            ),
+            closing_costs: proposed.get_total_fees(),
+            cash_costs: round(cash_costs, dec_places),
+            existing_npc: round(existing_npc, dec_places),
+            proposed_npc: round(proposed_npc, dec_places),
+            breakeven_date,
+        }
+    }
+
+    // positive when refinancing costs less in present value terms
+    pub fn get_npc_savings(&self) -> f64 {
+        self.existing_npc - self.proposed_npc
+    }
+}
+
+impl fmt::Display for RefiComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "refinance on {}, balance ${:.2}",
+            self.refi_date, self.existing_balance
+        )?;
+        writeln!(
+            f,
+            "  payment ${:.2} vs ${:.2}, difference ${:.2}",
+            self.existing_pmt, self.proposed_pmt, self.pmt_difference
+        )?;
+        writeln!(
+            f,
+            "  remaining interest ${:.2} vs ${:.2}",
+            self.existing_interest, self.proposed_interest
+        )?;
+        writeln!(
+            f,
+            "  net present cost ${:.2} vs ${:.2}, closing costs ${:.2}, paid in cash ${:.2}",
+            self.existing_npc, self.proposed_npc, self.closing_costs, self.cash_costs
+        )?;
+        match self.breakeven_date {
+            Some(date) => write!(f, "  cash costs recovered on {}", date),
+            None => write!(f, "  cash costs are never recovered"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new_refinance_loan, RefiComparison};
+    use crate::loan::{Compounding, Loan, PmtSchedule};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn existing_loan() -> Loan {
+        Loan::new(
+            300000.,
+            30.,
+            7.5,
+            PmtSchedule::Monthly,
+            Compounding::Monthly,
+            NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 10, 1).unwrap(),
+            2.,
+        )
+    }
+
+    #[test]
+    fn test_refinance_comparison() {
+        let existing = existing_loan();
+        let proposed = new_refinance_loan(
+            &existing,
+            NaiveDate::from_ymd_opt(2025, 9, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+            30.,
+            6.,
+            4000.,
+            false,
+        );
+        assert_eq!(
+            proposed.principal,
+            existing.get_balance(&proposed.loan_date)
+        );
+
+        let comparison = RefiComparison::new(&existing, &proposed, &6.);
+        assert_eq!(comparison.existing_pmt, 2097.64);
+        assert_eq!(comparison.proposed_pmt, 1764.2);
+        assert_eq!(comparison.pmt_difference, 333.44);
+        assert_eq!(comparison.closing_costs, 4000.);
+        assert_eq!(comparison.cash_costs, 4000.);
+
+        // the October payment on the existing loan is saved outright, then $333.44 a month
+        assert_eq!(
+            comparison.breakeven_date,
+            NaiveDate::from_ymd_opt(2026, 4, 1)
+        );
+        assert!(comparison.get_npc_savings() > 0.);
+    }
+
+    #[test]
+    fn test_refinance_after_prepayment() {
+        let mut existing = existing_loan();
+        let pmt_amount = existing.get_scheduled_pmts()[0].pmt_amount;
+        existing
+            .add_actual_pmt(1, NaiveDate::from_ymd_opt(2023, 10, 1).unwrap(), pmt_amount)
+            .unwrap();
+        existing
+            .add_actual_pmt(
+                2,
+                NaiveDate::from_ymd_opt(2023, 11, 1).unwrap(),
+                pmt_amount + 50000.,
+            )
+            .unwrap();
+        let refi_date = NaiveDate::from_ymd_opt(2025, 9, 15).unwrap();
+        let proposed = new_refinance_loan(
+            &existing,
+            refi_date,
+            NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+            30.,
+            6.,
+            4000.,
+            false,
+        );
+
+        // the remaining payments and interest follow the prepayment, like the balance
+        let comparison = RefiComparison::new(&existing, &proposed, &6.);
+        let remaining: Vec<_> = existing
+            .get_projected_pmts()
+            .into_iter()
+            .filter(|pmt| pmt.pmt_date > refi_date)
+            .collect();
+        assert!(comparison.existing_balance < 240000.);
+        assert_eq!(comparison.existing_balance, proposed.principal);
+        assert_eq!(comparison.existing_pmt, remaining[0].pmt_amount);
+        assert_eq!(
+            comparison.existing_interest,
+            (remaining
+                .iter()
+                .map(|pmt| pmt.pmt_interest_paid)
+                .sum::<f64>()
+                * 100.)
+                .round()
+                / 100.
+        );
+        let scheduled_interest: f64 = existing
+            .get_scheduled_pmts()
+            .iter()
+            .filter(|pmt| pmt.pmt_date > refi_date)
+            .map(|pmt| pmt.pmt_interest_paid)
+            .sum();
+        assert!(comparison.existing_interest < scheduled_interest - 100000.);
+    }
+
+    #[test]
+    fn test_no_breakeven() {
+        let existing = existing_loan();
+        let proposed = new_refinance_loan(
+            &existing,
+            NaiveDate::from_ymd_opt(2025, 9, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+            30.,
+            8.5,
+            4000.,
+            true,
+        );
+        let comparison = RefiComparison::new(&existing, &proposed, &6.);
+        assert_eq!(comparison.closing_costs, 4000.);
+        assert_eq!(comparison.cash_costs, 0.);
+        assert_eq!(comparison.breakeven_date, None);
+        assert!(comparison.get_npc_savings() < 0.);
+    }
+}
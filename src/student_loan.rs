@@ -0,0 +1,688 @@
+use crate::calendar::{BusinessDayConvention, HolidayCalendar};
+use crate::loan::{
+    get_first_pmt_amount, get_next_pmt_date, get_period_interest_rate, round, Compounding,
+    Forbearance, ForbearanceInterest, ForbearanceRepayment, Loan, LoanPayment, PmtDateRule,
+    PmtSchedule,
+};
+use chrono::{Months, NaiveDate};
+use log::trace;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const GRACE_MONTHS: u32 = 6; // repayment begins six months after the borrower leaves school
+const STANDARD_TERM: f64 = 10.;
+const EXTENDED_TERM: f64 = 25.;
+const GRADUATED_STEP_PMTS: i32 = 24; // graduated payments increase every two years
+const GRADUATED_STEP_INCREASE: f64 = 0.15; // each step is 15% larger than the one before
+const IDR_PCT_OF_DISCRETIONARY: f64 = 10.; // percent of discretionary income paid each year
+const IDR_POVERTY_MULTIPLE: f64 = 1.5; // income below 150% of the poverty guideline is protected
+const IDR_FORGIVENESS_PMTS: i32 = 240;
+const PSLF_PMTS: i32 = 120;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StudentLoanType {
+    Subsidized, // interest is paid by the government during deferment
+    #[default]
+    Unsubsidized,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RepaymentPlan {
+    #[default]
+    Standard, // level payments over 10 years
+    Graduated, // payments over 10 years that increase every two years
+    Extended,  // level payments over 25 years
+    IncomeDriven,
+}
+
+// poverty guidelines by family size. Families larger than the table add additional_person for
+// each person past the end of the table.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PovertyGuidelines {
+    pub amounts: Vec<f64>, // guideline for a family of 1, 2, ...
+    pub additional_person: f64,
+}
+
+impl PovertyGuidelines {
+    pub fn new(amounts: Vec<f64>, additional_person: f64) -> Self {
+        Self {
+            amounts,
+            additional_person,
+        }
+    }
+
+    // 2024 HHS poverty guidelines for the 48 contiguous states and the District of Columbia
+    pub fn us_contiguous_2024() -> Self {
+        Self::new(
+            vec![
+                15060., 20440., 25820., 31200., 36580., 41960., 47340., 52720.,
+            ],
+            5380.,
+        )
+    }
+
+    pub fn get_guideline(&self, &family_size: &u32) -> f64 {
+        let family_size = family_size.max(1) as usize;
+        match self.amounts.get(family_size - 1) {
+            Some(amount) => *amount,
+            None => {
+                self.amounts.last().copied().unwrap_or_default()
+                    + (family_size - self.amounts.len()) as f64 * self.additional_person
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BorrowerIncome {
+    pub agi: f64,
+    pub family_size: u32,
+    pub annual_growth: f64, // expected yearly growth in agi as a percent (i.e., 3.0)
+}
+
+impl BorrowerIncome {
+    pub fn new(agi: f64, family_size: u32, annual_growth: f64) -> Self {
+        Self {
+            agi,
+            family_size,
+            annual_growth,
+        }
+    }
+}
+
+// unpaid interest added to the principal balance
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CapitalizationEvent {
+    pub cap_date: NaiveDate,
+    pub amount: f64,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StudentLoanProjection {
+    pub repayment_balance: f64, // principal plus interest capitalized when repayment begins
+    pub capitalizations: Vec<CapitalizationEvent>,
+    pub pmts: Vec<LoanPayment>,
+    pub total_paid: f64,
+    pub total_interest: f64,
+    pub payoff_date: Option<NaiveDate>,
+    pub forgiveness_date: Option<NaiveDate>,
+    pub forgiven_amount: f64,
+}
+
+impl fmt::Display for StudentLoanProjection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |date: Option<NaiveDate>| match date {
+            Some(date) => date.to_string(),
+            None => "none".to_string(),
+        };
+        write!(
+            f,
+            "repayment balance ${:.2}, {} payments, total paid ${:.2}, interest ${:.2}, payoff {}, forgiveness {}, forgiven ${:.2}",
+            self.repayment_balance,
+            self.pmts.len(),
+            self.total_paid,
+            self.total_interest,
+            show(self.payoff_date),
+            show(self.forgiveness_date),
+            self.forgiven_amount
+        )
+    }
+}
+
+// a federal student loan: interest accrues while the borrower is in school and through the grace
+// period, then the loan is repaid monthly under a repayment plan. Interest is simple daily
+// interest on the principal balance. Repayment is projected on a Loan, so its payment date rule,
+// business day adjustment and forbearances apply.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StudentLoan {
+    pub principal: f64,
+    pub annual_rate: f64,
+    pub loan_type: StudentLoanType,
+    pub disbursement_date: NaiveDate,
+    pub separation_date: NaiveDate, // date the borrower leaves school
+    pub repayment_plan: RepaymentPlan,
+    pub dec_places: f64,
+    income: Option<BorrowerIncome>,
+    guidelines: PovertyGuidelines,
+    deferments: Vec<(i32, i32)>, // first payment number and number of payments deferred
+    pmt_date_rule: Option<PmtDateRule>,
+}
+
+impl StudentLoan {
+    pub fn new(
+        principal: f64,
+        annual_rate: f64,
+        loan_type: StudentLoanType,
+        disbursement_date: NaiveDate,
+        separation_date: NaiveDate,
+        dec_places: f64,
+    ) -> Self {
+        Self {
+            principal,
+            annual_rate,
+            loan_type,
+            disbursement_date,
+            separation_date,
+            repayment_plan: RepaymentPlan::Standard,
+            dec_places,
+            income: None,
+            guidelines: PovertyGuidelines::us_contiguous_2024(),
+            deferments: Vec::new(),
+            pmt_date_rule: None,
+        }
+    }
+
+    pub fn set_repayment_plan(&mut self, repayment_plan: RepaymentPlan) {
+        self.repayment_plan = repayment_plan;
+    }
+
+    // income and poverty guidelines used for the income-driven payment
+    pub fn set_income(&mut self, income: BorrowerIncome, guidelines: PovertyGuidelines) {
+        self.income = Some(income);
+        self.guidelines = guidelines;
+    }
+
+    pub fn get_income(&self) -> Option<&BorrowerIncome> {
+        self.income.as_ref()
+    }
+
+    // defer pmt_count payments from pmt_number during repayment. Unsubsidized interest accrues
+    // and is capitalized when the deferment ends.
+    pub fn add_deferment(&mut self, pmt_number: i32, pmt_count: i32) {
+        self.deferments.push((pmt_number, pmt_count));
+    }
+
+    fn is_deferred(&self, &pmt_number: &i32) -> bool {
+        self.deferments
+            .iter()
+            .any(|(start, count)| pmt_number >= *start && pmt_number < start + count)
+    }
+
+    // how repayment due dates fall within the month. By default payments are due on the day of
+    // the month repayment begins.
+    pub fn set_pmt_date_rule(&mut self, pmt_date_rule: PmtDateRule) {
+        self.pmt_date_rule = Some(pmt_date_rule);
+    }
+
+    pub fn get_pmt_date_rule(&self) -> PmtDateRule {
+        match &self.pmt_date_rule {
+            Some(pmt_date_rule) => pmt_date_rule.clone(),
+            None => PmtDateRule::from_first_pmt_date(&self.get_repayment_date()),
+        }
+    }
+
+    // move payments due on a weekend or holiday to a business day
+    pub fn set_business_day_adjustment(
+        &mut self,
+        business_day_convention: BusinessDayConvention,
+        holiday_calendar: HolidayCalendar,
+    ) {
+        self.set_pmt_date_rule(PmtDateRule {
+            business_day_convention,
+            holiday_calendar,
+            ..self.get_pmt_date_rule()
+        });
+    }
+
+    // the end of the grace period, when interest is capitalized and repayment begins
+    pub fn get_repayment_date(&self) -> NaiveDate {
+        self.separation_date
+            .checked_add_months(Months::new(GRACE_MONTHS))
+            .unwrap()
+    }
+
+    // simple interest on balance from begin_date to end_date
+    fn get_interest(&self, &balance: &f64, &begin_date: &NaiveDate, &end_date: &NaiveDate) -> f64 {
+        balance
+            * get_period_interest_rate(
+                &self.annual_rate,
+                &PmtSchedule::Monthly,
+                &Compounding::Simple,
+                &begin_date,
+                &end_date,
+            )
+    }
+
+    // interest accrued in school and through the grace period. It is capitalized when repayment
+    // begins unless the loan is subsidized.
+    pub fn get_deferment_interest(&self) -> f64 {
+        match self.loan_type {
+            StudentLoanType::Subsidized => 0.,
+            StudentLoanType::Unsubsidized => round(
+                self.get_interest(
+                    &self.principal,
+                    &self.disbursement_date,
+                    &self.get_repayment_date(),
+                ),
+                self.dec_places,
+            ),
+        }
+    }
+
+    pub fn get_repayment_balance(&self) -> f64 {
+        round(
+            self.principal + self.get_deferment_interest(),
+            self.dec_places,
+        )
+    }
+
+    // level monthly payments of the repayment balance over term years, from the end of the grace
+    // period
+    fn get_term_loan(&self, &term: &f64) -> Loan {
+        let repayment_date = self.get_repayment_date();
+        let pmt_date_rule = self.get_pmt_date_rule();
+        let mut loan = Loan::new(
+            self.get_repayment_balance(),
+            term,
+            self.annual_rate,
+            PmtSchedule::Monthly,
+            Compounding::Simple,
+            repayment_date,
+            get_next_pmt_date(&repayment_date, &PmtSchedule::Monthly, &pmt_date_rule),
+            self.dec_places,
+        );
+        loan.set_pmt_date_rule(pmt_date_rule);
+        loan
+    }
+
+    // years of payments under the repayment plan. Income-driven payments run until forgiveness.
+    fn get_plan_term(&self) -> f64 {
+        match self.repayment_plan {
+            RepaymentPlan::Standard | RepaymentPlan::Graduated => STANDARD_TERM,
+            RepaymentPlan::Extended => EXTENDED_TERM,
+            RepaymentPlan::IncomeDriven => IDR_FORGIVENESS_PMTS as f64 / 12.,
+        }
+    }
+
+    // the loan repaid under the repayment plan. Deferments suspend its payments as forbearances,
+    // and a fixed-term plan is recast after each one so it still ends after the plan's payments.
+    pub fn get_repayment_loan(&self) -> Loan {
+        let mut loan = self.get_term_loan(&self.get_plan_term());
+        let interest = match self.loan_type {
+            StudentLoanType::Subsidized => ForbearanceInterest::Waived,
+            StudentLoanType::Unsubsidized => ForbearanceInterest::Capitalized,
+        };
+        for &(start_pmt, pmt_count) in &self.deferments {
+            loan.add_forbearance(Forbearance::new(
+                start_pmt,
+                pmt_count,
+                interest,
+                ForbearanceRepayment::DeferToMaturity,
+            ));
+        }
+        if self.get_plan_pmt_count().is_some() {
+            for &(start_pmt, pmt_count) in &self.deferments {
+                if pmt_count <= 0 {
+                    continue;
+                }
+                let last_deferred = (start_pmt.max(1) + pmt_count - 2) as usize;
+                if let Some(pmt) = loan.get_scheduled_pmts().get(last_deferred).copied() {
+                    loan.recast(pmt.pmt_date);
+                }
+            }
+        }
+        loan
+    }
+
+    // level monthly payment over term years on the repayment balance
+    fn get_level_pmt(&self, &term: &f64) -> f64 {
+        *self.get_term_loan(&term).get_pmt_amount()
+    }
+
+    pub fn get_standard_pmt(&self) -> f64 {
+        self.get_level_pmt(&STANDARD_TERM)
+    }
+
+    // first graduated payment: the payments step up every two years and retire the balance over
+    // the standard term
+    pub fn get_graduated_pmt(&self) -> f64 {
+        let pmt_count = (STANDARD_TERM * 12.) as usize;
+        let pmt_dates: Vec<NaiveDate> = self
+            .get_term_loan(&STANDARD_TERM)
+            .get_scheduled_pmts()
+            .iter()
+            .take(pmt_count)
+            .map(|pmt| pmt.pmt_date)
+            .collect();
+        get_first_pmt_amount(
+            &self.get_repayment_balance(),
+            &self.get_repayment_date(),
+            &pmt_dates,
+            |pmt_number| {
+                (1. + GRADUATED_STEP_INCREASE).powi((pmt_number as i32 - 1) / GRADUATED_STEP_PMTS)
+            },
+            &self.annual_rate,
+            &Compounding::Simple,
+            &self.dec_places,
+        )
+    }
+
+    // monthly income-driven payment: a percent of the income above 150% of the poverty guideline,
+    // no more than the standard payment
+    pub fn get_idr_pmt(&self, &agi: &f64, &family_size: &u32) -> f64 {
+        self.get_capped_idr_pmt(&agi, &family_size, &self.get_standard_pmt())
+    }
+
+    fn get_capped_idr_pmt(&self, &agi: &f64, &family_size: &u32, &standard_pmt: &f64) -> f64 {
+        let protected = IDR_POVERTY_MULTIPLE * self.guidelines.get_guideline(&family_size);
+        let annual_pmt = (agi - protected).max(0.) * IDR_PCT_OF_DISCRETIONARY / 100.;
+        round((annual_pmt / 12.).min(standard_pmt), self.dec_places)
+    }
+
+    // the first payment under the repayment plan
+    fn get_base_pmt(&self) -> f64 {
+        match self.repayment_plan {
+            RepaymentPlan::Standard | RepaymentPlan::IncomeDriven => self.get_standard_pmt(),
+            RepaymentPlan::Extended => self.get_level_pmt(&EXTENDED_TERM),
+            RepaymentPlan::Graduated => self.get_graduated_pmt(),
+        }
+    }
+
+    // payment due on the qualifying payment numbered pmt_number, given the plan's base payment
+    fn get_plan_pmt(&self, &pmt_number: &i32, &base_pmt: &f64) -> f64 {
+        match self.repayment_plan {
+            RepaymentPlan::Standard | RepaymentPlan::Extended => base_pmt,
+            RepaymentPlan::Graduated => round(
+                base_pmt
+                    * (1. + GRADUATED_STEP_INCREASE).powi((pmt_number - 1) / GRADUATED_STEP_PMTS),
+                self.dec_places,
+            ),
+            RepaymentPlan::IncomeDriven => match &self.income {
+                // income is recertified each year
+                Some(income) => self.get_capped_idr_pmt(
+                    &(income.agi * (1. + income.annual_growth / 100.).powi((pmt_number - 1) / 12)),
+                    &income.family_size,
+                    &base_pmt,
+                ),
+                None => base_pmt,
+            },
+        }
+    }
+
+    // payments to repay the loan under a fixed-term plan. The last payment pays off the balance.
+    fn get_plan_pmt_count(&self) -> Option<i32> {
+        match self.repayment_plan {
+            RepaymentPlan::Standard | RepaymentPlan::Graduated => {
+                Some((STANDARD_TERM * 12.) as i32)
+            }
+            RepaymentPlan::Extended => Some((EXTENDED_TERM * 12.) as i32),
+            RepaymentPlan::IncomeDriven => None,
+        }
+    }
+
+    // qualifying payments after which any balance is forgiven. Public Service Loan Forgiveness
+    // requires the standard or an income-driven plan.
+    fn get_forgiveness_pmts(&self, &pslf: &bool) -> Option<i32> {
+        match (self.repayment_plan, pslf) {
+            (RepaymentPlan::Standard | RepaymentPlan::IncomeDriven, true) => Some(PSLF_PMTS),
+            (RepaymentPlan::IncomeDriven, false) => Some(IDR_FORGIVENESS_PMTS),
+            _ => None,
+        }
+    }
+
+    // project the balance through repayment. pslf projects forgiveness under Public Service Loan
+    // Forgiveness.
+    pub fn get_projection(&self, &pslf: &bool) -> StudentLoanProjection {
+        let dec_places = self.dec_places;
+        let mut projection = StudentLoanProjection {
+            repayment_balance: self.get_repayment_balance(),
+            ..Default::default()
+        };
+        if self.get_deferment_interest() > 0. {
+            projection.capitalizations.push(CapitalizationEvent {
+                cap_date: self.get_repayment_date(),
+                amount: self.get_deferment_interest(),
+            });
+        }
+
+        // the loan's scheduled payments give the due dates; the plan sets the amounts
+        let loan = self.get_repayment_loan();
+        let forgiveness_pmts = self.get_forgiveness_pmts(&pslf);
+        let plan_pmt_count = self.get_plan_pmt_count();
+        let base_pmt = self.get_base_pmt();
+        let mut balance = projection.repayment_balance;
+        let mut unpaid_interest = 0.;
+        let mut begin_date = self.get_repayment_date();
+        let mut qualifying_pmts = 0;
+        let mut pmt_scale = 1.; // fixed-term payments grow with interest capitalized in repayment
+        for scheduled_pmt in loan.get_scheduled_pmts() {
+            if balance <= 0. {
+                break;
+            }
+            let pmt_number = scheduled_pmt.pmt_number;
+            let pmt_date = scheduled_pmt.pmt_date;
+            let interest = self.get_interest(&balance, &begin_date, &pmt_date);
+            begin_date = pmt_date;
+
+            if self.is_deferred(&pmt_number) {
+                if self.loan_type == StudentLoanType::Unsubsidized {
+                    unpaid_interest += interest;
+                }
+                if !self.is_deferred(&(pmt_number + 1)) && unpaid_interest > 0. {
+                    let amount = round(unpaid_interest, dec_places);
+                    trace!("capitalize {} on {}", amount, pmt_date);
+                    projection.capitalizations.push(CapitalizationEvent {
+                        cap_date: pmt_date,
+                        amount,
+                    });
+                    if plan_pmt_count.is_some() {
+                        pmt_scale *= (balance + amount) / balance;
+                    }
+                    balance = round(balance + amount, dec_places);
+                    unpaid_interest = 0.;
+                }
+                projection
+                    .pmts
+                    .push(LoanPayment::new(pmt_number, pmt_date, 0., 0., balance));
+                continue;
+            }
+
+            // interest is paid before principal; interest a payment does not cover is carried
+            // forward without being capitalized
+            qualifying_pmts += 1;
+            let interest_due = unpaid_interest + interest;
+            let mut pmt_amount = round(
+                self.get_plan_pmt(&qualifying_pmts, &base_pmt) * pmt_scale,
+                dec_places,
+            );
+            if pmt_amount >= balance + interest_due || Some(qualifying_pmts) == plan_pmt_count {
+                pmt_amount = balance + interest_due;
+                balance = 0.;
+                unpaid_interest = 0.;
+            } else if pmt_amount >= interest_due {
+                balance = round(balance - (pmt_amount - interest_due), dec_places);
+                unpaid_interest = 0.;
+            } else {
+                unpaid_interest = interest_due - pmt_amount;
+            }
+
+            let interest_paid = round(pmt_amount.min(interest_due), dec_places);
+            projection.pmts.push(LoanPayment::new(
+                pmt_number,
+                pmt_date,
+                round(pmt_amount, dec_places),
+                interest_paid,
+                balance,
+            ));
+            projection.total_paid += pmt_amount;
+            projection.total_interest += interest_paid;
+
+            if balance > 0. && Some(qualifying_pmts) == forgiveness_pmts {
+                projection.forgiveness_date = Some(pmt_date);
+                projection.forgiven_amount = round(balance + unpaid_interest, dec_places);
+                break;
+            }
+        }
+
+        if balance <= 0. {
+            projection.payoff_date = projection.pmts.last().map(|pmt| pmt.pmt_date);
+        }
+        projection.total_paid = round(projection.total_paid, dec_places);
+        projection.total_interest = round(projection.total_interest, dec_places);
+        projection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BorrowerIncome, PovertyGuidelines, RepaymentPlan, StudentLoan, StudentLoanType};
+    use crate::calendar::{BusinessDayConvention, HolidayCalendar};
+    use chrono::NaiveDate;
+    use test_log::test;
+
+    fn new_loan(loan_type: StudentLoanType) -> StudentLoan {
+        StudentLoan::new(
+            30000.,
+            5.5,
+            loan_type,
+            NaiveDate::from_ymd_opt(2020, 9, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 5, 15).unwrap(),
+            2.,
+        )
+    }
+
+    #[test]
+    fn test_deferment_interest() {
+        let subsidized = new_loan(StudentLoanType::Subsidized);
+        assert_eq!(subsidized.get_deferment_interest(), 0.);
+        assert_eq!(subsidized.get_repayment_balance(), 30000.);
+
+        // 1,536 days from disbursement to the end of the grace period on November 15, 2024
+        let loan = new_loan(StudentLoanType::Unsubsidized);
+        assert_eq!(
+            loan.get_repayment_date(),
+            NaiveDate::from_ymd_opt(2024, 11, 15).unwrap()
+        );
+        assert_eq!(loan.get_deferment_interest(), 6943.56);
+        assert_eq!(loan.get_repayment_balance(), 36943.56);
+
+        let projection = loan.get_projection(&false);
+        assert_eq!(projection.capitalizations.len(), 1);
+        assert_eq!(projection.capitalizations[0].amount, 6943.56);
+    }
+
+    #[test]
+    fn test_repayment_plans() {
+        let mut loan = new_loan(StudentLoanType::Subsidized);
+        let standard = loan.get_projection(&false);
+        assert_eq!(loan.get_standard_pmt(), 325.59);
+        assert_eq!(standard.pmts.len(), 120);
+        assert_eq!(standard.payoff_date, NaiveDate::from_ymd_opt(2034, 11, 15));
+        assert_eq!(standard.forgiveness_date, None);
+
+        // the level payment is solved under the projection's daily interest, so the last
+        // payment differs from the others only by the rounding of the payment
+        assert!((standard.pmts[119].pmt_amount - loan.get_standard_pmt()).abs() < 0.25);
+
+        loan.set_repayment_plan(RepaymentPlan::Graduated);
+        let graduated = loan.get_projection(&false);
+        assert!(loan.get_graduated_pmt() < loan.get_standard_pmt());
+        assert!(graduated.pmts[24].pmt_amount > graduated.pmts[23].pmt_amount);
+        assert_eq!(graduated.pmts.len(), 120);
+        assert!(graduated.total_interest > standard.total_interest);
+
+        loan.set_repayment_plan(RepaymentPlan::Extended);
+        let extended = loan.get_projection(&false);
+        assert_eq!(extended.pmts.len(), 300);
+        assert!(extended.total_interest > graduated.total_interest);
+    }
+
+    #[test]
+    fn test_income_driven() {
+        let guidelines = PovertyGuidelines::us_contiguous_2024();
+        assert_eq!(guidelines.get_guideline(&1), 15060.);
+        assert_eq!(guidelines.get_guideline(&10), 63480.);
+
+        let mut loan = new_loan(StudentLoanType::Unsubsidized);
+        loan.set_repayment_plan(RepaymentPlan::IncomeDriven);
+        loan.set_income(BorrowerIncome::new(45000., 2, 3.), guidelines);
+
+        // 10% of the income above 150% of the guideline for a family of two
+        assert_eq!(loan.get_idr_pmt(&45000., &2), 119.5);
+        assert_eq!(loan.get_idr_pmt(&20000., &2), 0.);
+        assert_eq!(loan.get_idr_pmt(&500000., &2), loan.get_standard_pmt());
+
+        // the payment does not cover the interest, so the balance is forgiven after 20 years
+        let projection = loan.get_projection(&false);
+        assert_eq!(projection.pmts[0].pmt_amount, 119.5);
+        assert_eq!(projection.pmts.len(), 240);
+        assert_eq!(
+            projection.forgiveness_date,
+            NaiveDate::from_ymd_opt(2044, 11, 15)
+        );
+        assert!(projection.forgiven_amount > 0.);
+        assert_eq!(projection.payoff_date, None);
+
+        // public service forgiveness after 10 years of qualifying payments
+        let pslf = loan.get_projection(&true);
+        assert_eq!(pslf.pmts.len(), 120);
+        assert_eq!(pslf.forgiveness_date, NaiveDate::from_ymd_opt(2034, 11, 15));
+        assert!(pslf.total_paid < projection.total_paid);
+    }
+
+    #[test]
+    fn test_deferment_during_repayment() {
+        let mut loan = new_loan(StudentLoanType::Unsubsidized);
+        loan.add_deferment(13, 6);
+        let projection = loan.get_projection(&false);
+
+        assert_eq!(projection.pmts[12].pmt_amount, 0.);
+        assert_eq!(projection.capitalizations.len(), 2);
+        assert_eq!(
+            projection.capitalizations[1].cap_date,
+            NaiveDate::from_ymd_opt(2026, 5, 15).unwrap()
+        );
+        assert_eq!(
+            projection.pmts[17].pmt_end_balance,
+            projection.pmts[11].pmt_end_balance + projection.capitalizations[1].amount
+        );
+        // the standard payment rises to repay the capitalized interest over the same payments
+        assert_eq!(projection.pmts.len(), 126);
+        assert!(projection.pmts[18].pmt_amount > projection.pmts[11].pmt_amount);
+        assert!(projection.pmts[125].pmt_amount < projection.pmts[18].pmt_amount * 1.05);
+    }
+
+    #[test]
+    fn test_repayment_loan() {
+        let mut loan = new_loan(StudentLoanType::Subsidized);
+        let standard = loan.get_projection(&false);
+        let repayment_loan = loan.get_repayment_loan();
+        assert_eq!(*repayment_loan.get_pmt_amount(), loan.get_standard_pmt());
+        // the projection rounds its balance each month
+        assert!(
+            (repayment_loan.get_scheduled_pmts()[60].pmt_end_balance
+                - standard.pmts[60].pmt_end_balance)
+                .abs()
+                < 0.1
+        );
+
+        // interest on a subsidized loan is waived during the deferment
+        loan.add_deferment(13, 6);
+        let repayment_loan = loan.get_repayment_loan();
+        let pmts = repayment_loan.get_scheduled_pmts();
+        assert_eq!(pmts.len(), 126);
+        assert_eq!(pmts[12].pmt_amount, 0.);
+        assert_eq!(pmts[17].pmt_end_balance, pmts[11].pmt_end_balance);
+        assert_eq!(loan.get_projection(&false).capitalizations.len(), 0);
+
+        // February 15, 2025 is a Saturday
+        loan.set_business_day_adjustment(BusinessDayConvention::Following, HolidayCalendar::None);
+        let projection = loan.get_projection(&false);
+        assert_eq!(
+            projection.pmts[2].pmt_date,
+            NaiveDate::from_ymd_opt(2025, 2, 17).unwrap()
+        );
+        assert_eq!(
+            projection.pmts[3].pmt_date,
+            NaiveDate::from_ymd_opt(2025, 3, 17).unwrap()
+        );
+    }
+}